@@ -7,14 +7,18 @@
 //! ![Good luck GIF](https://github.com/me-shaon/GLWTPL/blob/master/good-luck.gif?raw=true)
 
 use egui::Color32;
-use egui_extras::{Column, TableBuilder};
+use egui_extras::{Column, TableBuilder, TableRow};
 use fuzzy_matcher::FuzzyMatcher;
 use humansize::{format_size, DECIMAL};
 use indexmap::IndexMap;
 use std::{
+    collections::{BTreeSet, HashMap, HashSet, VecDeque},
     io::Cursor,
-    path::PathBuf,
-    sync::{Arc, RwLock},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Condvar, Mutex, RwLock,
+    },
 };
 
 const DATABASE_URL: &str = "https://raw.githubusercontent.com/zeozeozeo/clickpack-db/main/db.json";
@@ -22,38 +26,381 @@ const DATABASE_URL: &str = "https://raw.githubusercontent.com/zeozeozeo/clickpac
 #[cfg(not(feature = "live"))]
 const TEMP_DIRNAME: &str = "zcb-clickpackdb";
 
+const STATE_FILENAME: &str = "state.json";
+const FAVORITES_FILENAME: &str = "favorites.json";
+const CUSTOM_TAGS_FILENAME: &str = "custom_tags.json";
+const DB_CACHE_FILENAME: &str = "db_cache.json";
+const LOCAL_OVERRIDES_FILENAME: &str = "local_overrides.json";
+
+const DEFAULT_MAX_CONCURRENT_DOWNLOADS: usize = 3;
+const DEFAULT_MAX_RETRIES: u8 = 3;
+const DEFAULT_PAGE_SIZE: usize = 50;
+const DEFAULT_CACHE_TTL_SECS: u64 = 60 * 60 * 24;
+const DEFAULT_ROW_HEIGHT_MULTIPLIER: f32 = 1.5;
+/// How long the Refresh button stays disabled after the database was fetched.
+const REFRESH_COOLDOWN: std::time::Duration = std::time::Duration::from_secs(60);
+const SEARCH_HISTORY_LEN: usize = 10;
+
+const NAME_COLUMN_WIDTH: f32 = 200.0;
+
+/// How long a row stays highlighted after jumping to it.
+const HIGHLIGHT_DURATION: std::time::Duration = std::time::Duration::from_millis(1500);
+
 type RequestFn = dyn Fn(&str) -> Result<Vec<u8>, String> + Sync;
 
+/// Like [`RequestFn`], but reports `(received, total)` byte counts through the
+/// provided callback while the body is being read.
+type StreamingRequestFn =
+    dyn Fn(&str, &dyn Fn(usize, Option<usize>)) -> Result<Vec<u8>, String> + Sync;
+
+/// Like [`RequestFn`], but also takes HTTP headers to send with the request.
+type RequestWithHeadersFn =
+    dyn Fn(&str, &HashMap<String, String>) -> Result<Vec<u8>, String> + Sync;
+
+/// Called with the name and directory of a clickpack that finished downloading.
+type DownloadCompleteFn = dyn Fn(&str, &PathBuf);
+
+type DownloadProgress = Arc<Mutex<(usize, Option<usize>)>>;
+
+/// Decides whether an entry is shown, given its name, see [`ClickpackDb::set_custom_filter`].
+type CustomFilterFn = dyn Fn(&str, &Entry) -> bool + Send + Sync;
+
+/// Name of an entry and the bytes of its audio preview.
+type PreviewAudio = (String, Vec<u8>);
+
 #[cfg(not(feature = "live"))]
 type PickFolderFn = dyn Fn() -> Option<PathBuf> + Sync;
 
+/// Shared flag used to cancel an in-progress download.
+#[derive(Clone, Default, Debug)]
+struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Default)]
+struct SlotState {
+    used: usize,
+    limit: usize,
+    /// Tickets of the downloads waiting for a slot, first in line first.
+    waiting: std::collections::VecDeque<u64>,
+    next_ticket: u64,
+}
+
+/// Counting semaphore that limits how many downloads run at the same time.
+/// Waiting downloads get a slot in the order they asked for one.
+#[derive(Debug)]
+struct DownloadSlots {
+    slots: Mutex<SlotState>,
+    freed: Condvar,
+}
+
+impl Default for DownloadSlots {
+    fn default() -> Self {
+        Self {
+            slots: Mutex::new(SlotState {
+                limit: DEFAULT_MAX_CONCURRENT_DOWNLOADS,
+                ..Default::default()
+            }),
+            freed: Condvar::new(),
+        }
+    }
+}
+
+impl DownloadSlots {
+    fn set_limit(&self, limit: usize) {
+        self.slots.lock().unwrap().limit = limit.max(1);
+        self.freed.notify_all();
+    }
+
+    /// Blocks until a slot is free. The slot is released when the guard is dropped.
+    ///
+    /// While waiting, `on_position` is called with the 1-based position in the
+    /// queue whenever it changes. Returns [`None`] if cancelled while waiting.
+    fn acquire(
+        self: &Arc<Self>,
+        cancel: &CancelToken,
+        on_position: impl Fn(usize),
+    ) -> Option<DownloadSlotGuard> {
+        let mut slots = self.slots.lock().unwrap();
+        let ticket = slots.next_ticket;
+        slots.next_ticket += 1;
+        slots.waiting.push_back(ticket);
+        let mut last_position = 0;
+        loop {
+            let position = slots.waiting.iter().position(|&t| t == ticket).unwrap_or(0);
+            if cancel.is_cancelled() {
+                slots.waiting.remove(position);
+                drop(slots);
+                // the ones behind this download moved forward
+                self.freed.notify_all();
+                return None;
+            }
+            if position == 0 && slots.used < slots.limit {
+                slots.waiting.pop_front();
+                slots.used += 1;
+                drop(slots);
+                self.freed.notify_all();
+                return Some(DownloadSlotGuard(self.clone()));
+            }
+            if position + 1 != last_position {
+                last_position = position + 1;
+                on_position(last_position);
+            }
+            // cancelling doesn't notify the condvar, so wake up now and then to check
+            slots = self
+                .freed
+                .wait_timeout(slots, std::time::Duration::from_millis(200))
+                .unwrap()
+                .0;
+        }
+    }
+}
+
+struct DownloadSlotGuard(Arc<DownloadSlots>);
+
+impl Drop for DownloadSlotGuard {
+    fn drop(&mut self) {
+        self.0.slots.lock().unwrap().used -= 1;
+        self.0.freed.notify_all();
+    }
+}
+
 #[derive(Clone, Default, Debug)]
 enum DownloadStatus {
     #[default]
     NotDownloaded,
-    Downloading,
+    /// Waiting for a free download slot, see [`ClickpackDbConfig::max_concurrent_downloads`].
+    Queued {
+        /// 1-based position in the download queue.
+        position: usize,
+        cancel: CancelToken,
+    },
+    Downloading {
+        started_at: std::time::Instant,
+        received: usize,
+        total: Option<usize>,
+        cancel: CancelToken,
+    },
+    /// The last attempt failed and the download will be retried after a delay.
+    Retrying {
+        attempt: u8,
+        max: u8,
+        error: String,
+        cancel: CancelToken,
+        /// Which mirror is tried next and how many there are, if the main URL failed.
+        mirror: Option<(usize, usize)>,
+    },
     Downloaded {
         path: PathBuf,
         do_select: bool,
+        /// `false` if only the ZIP file was saved to `path`.
+        is_extracted: bool,
     },
     Error(String),
 }
 
-#[derive(serde::Deserialize, Default)]
+/// What [`ClickpackDb::download_entry`] does with the downloaded ZIP file.
+#[derive(Clone, Copy)]
+enum DownloadMode {
+    /// Extract it into the directory and select the clickpack if `do_select` is set.
+    Extract { do_select: bool },
+    /// Save it as `<name>.zip` in the directory without extracting it.
+    Zip,
+    /// Extract a new version over an already downloaded clickpack. The old
+    /// files are removed first if the widget created the directory.
+    Update,
+}
+
+/// Where [`ClickpackDb::download_entry`] puts a clickpack.
+#[derive(Clone)]
+struct DownloadTarget {
+    path: PathBuf,
+    mode: DownloadMode,
+    /// `path` was created by [`ClickpackDb::unique_download_path`] and can be
+    /// removed if the download doesn't finish. Folders picked by the user are
+    /// never removed.
+    owns_dir: bool,
+}
+
+impl DownloadStatus {
+    /// Cancel token of a queued, in-progress or retrying download.
+    const fn cancel_token(&self) -> Option<&CancelToken> {
+        match self {
+            Self::Queued { cancel, .. }
+            | Self::Downloading { cancel, .. }
+            | Self::Retrying { cancel, .. } => Some(cancel),
+            _ => None,
+        }
+    }
+
+    /// Label of the [`DownloadStatus::Retrying`] status.
+    fn retrying_label(attempt: u8, max: u8, mirror: Option<(usize, usize)>) -> String {
+        match mirror {
+            Some((mirror, nr_mirrors)) => format!("Trying mirror {mirror}/{nr_mirrors}…"),
+            None => format!("Retrying ({attempt}/{max})…"),
+        }
+    }
+
+    fn downloading(cancel: CancelToken) -> Self {
+        Self::Downloading {
+            started_at: std::time::Instant::now(),
+            received: 0,
+            total: None,
+            cancel,
+        }
+    }
+
+    /// Whether the download finished, successfully or not.
+    const fn is_terminal(&self) -> bool {
+        matches!(self, Self::Downloaded { .. } | Self::Error(_))
+    }
+
+    /// Whether the download is queued, in progress or waiting for a retry.
+    const fn is_in_progress(&self) -> bool {
+        matches!(
+            self,
+            Self::Queued { .. } | Self::Downloading { .. } | Self::Retrying { .. }
+        )
+    }
+}
+
+impl std::fmt::Display for DownloadStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotDownloaded => write!(f, "not downloaded"),
+            Self::Queued { position, .. } => write!(f, "queued (#{position})"),
+            Self::Downloading { .. } => write!(f, "downloading"),
+            Self::Retrying { attempt, max, .. } => write!(f, "retrying ({attempt}/{max})"),
+            Self::Downloaded { path, .. } => write!(f, "downloaded to {}", path.display()),
+            Self::Error(e) => write!(f, "error: {e}"),
+        }
+    }
+}
+
+/// Formats the download speed and, if the total size is known, the remaining
+/// time, e.g. "1.2 MB/s · ETA 8 s".
+fn format_speed_eta(
+    started_at: std::time::Instant,
+    received: usize,
+    total: Option<usize>,
+) -> String {
+    let bytes_per_sec = received as f64 / started_at.elapsed().as_secs_f64().max(0.001);
+    let speed = format!("{}/s", format_size(bytes_per_sec as usize, DECIMAL));
+    match total {
+        Some(total) if bytes_per_sec > 0.0 => {
+            let eta = (total.saturating_sub(received) as f64 / bytes_per_sec).ceil();
+            format!("{speed} · ETA {eta} s")
+        }
+        _ => speed,
+    }
+}
+
+/// Sleeps until `received` bytes since `started_at` are no faster than `max_bps`.
+fn throttle(started_at: std::time::Instant, received: usize, max_bps: usize) {
+    let min_elapsed = std::time::Duration::from_secs_f64(received as f64 / max_bps as f64);
+    if let Some(delay) = min_elapsed.checked_sub(started_at.elapsed()) {
+        std::thread::sleep(delay);
+    }
+}
+
+/// Delay before retrying a failed download: `2^attempt * 500ms`, capped at 8 seconds.
+fn retry_delay(attempt: u8) -> std::time::Duration {
+    std::time::Duration::from_millis(500u64.saturating_mul(1 << attempt.min(16)))
+        .min(std::time::Duration::from_secs(8))
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Default)]
 pub struct Database {
     pub updated_at_unix: i64,
     #[serde(rename = "clickpacks")]
     pub entries: IndexMap<String, Entry>,
 }
 
-#[derive(serde::Deserialize, Clone)]
+impl Database {
+    /// Serializes the database back into the `db.json` format. Download
+    /// statuses and other runtime state are not included.
+    pub fn to_json_bytes(&self) -> Result<Vec<u8>, serde_json::Error> {
+        serde_json::to_vec(self)
+    }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Default)]
 pub struct Entry {
-    size: usize,
-    uncompressed_size: usize,
-    has_noise: bool,
-    url: String,
-    #[serde(skip_deserializing)]
+    /// Size of the ZIP file, in bytes.
+    pub size: usize,
+    /// Size of the extracted clickpack, in bytes.
+    pub uncompressed_size: usize,
+    pub has_noise: bool,
+    /// Whether the clickpack has softclick sounds.
+    #[serde(default)]
+    pub has_softclicks: bool,
+    /// Whether the clickpack has hardclick sounds.
+    #[serde(default)]
+    pub has_hardclicks: bool,
+    /// Number of times the clickpack was downloaded from the database.
+    #[serde(default)]
+    pub downloads: u32,
+    /// Highlighted by the database maintainer, e.g. an editor's pick. Featured
+    /// entries are listed first.
+    #[serde(default)]
+    pub featured: bool,
+    /// URL of the ZIP file.
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Official taxonomy tags, e.g. `["soft", "mechanical", "phone"]`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Bots the clickpack works with, e.g. `["zcb", "GDMO"]`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub compatible_with: Vec<String>,
+    /// Hex SHA-256 of the ZIP file, checked after downloading.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// Version of the clickpack, e.g. a semver string or a commit hash. Changes
+    /// when the clickpack is updated without changing its URL.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// URLs to download the ZIP file from if `url` fails, tried in order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mirror_urls: Vec<String>,
+    /// License of the sounds, e.g. `"CC0"` or `"CC-BY-4.0"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    /// URL of a short audio preview of the clickpack.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preview_url: Option<String>,
+    #[serde(skip)]
     dwn_status: DownloadStatus,
+    /// Imported from a local ZIP file instead of coming from the database.
+    #[serde(skip)]
+    is_local: bool,
+}
+
+impl Entry {
+    /// Directory the clickpack was downloaded to, or the ZIP file if it wasn't
+    /// extracted, if it is downloaded.
+    pub fn downloaded_path(&self) -> Option<&Path> {
+        match self.dwn_status {
+            DownloadStatus::Downloaded { ref path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Whether this entry was imported from a local ZIP file.
+    pub const fn is_local(&self) -> bool {
+        self.is_local
+    }
 }
 
 #[derive(Default, Clone)]
@@ -65,47 +412,838 @@ pub enum Status {
     Loaded {
         did_filter: bool,
     },
+    /// The database couldn't be fetched, so the cached copy from a previous
+    /// session is shown instead.
+    LoadedFromCache {
+        did_filter: bool,
+        /// When the cache was written.
+        cached_at_unix: i64,
+    },
 }
 
-#[derive(Default)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum SortColumn {
+    Name,
+    Size,
+    UncompressedSize,
+    Downloads,
+    Noise,
+}
+
+impl SortColumn {
+    const ALL: [Self; 5] = [
+        Self::Name,
+        Self::Size,
+        Self::UncompressedSize,
+        Self::Downloads,
+        Self::Noise,
+    ];
+
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Name => "Name",
+            Self::Size => "Size",
+            Self::UncompressedSize => "Uncompressed size",
+            Self::Downloads => "Downloads",
+            Self::Noise => "Noise",
+        }
+    }
+}
+
+/// Order of the table when no column is selected in "Sort by", see
+/// [`ClickpackDb::with_default_order`]. A search query still orders by match score.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum DatabaseOrder {
+    /// Order of the entries in `db.json`.
+    #[default]
+    ByInsertionOrder,
+    /// Alphabetical, ignoring case.
+    ByName,
+    /// Smallest ZIP file first.
+    BySize,
+    /// Most downloaded first.
+    ByDownloads,
+}
+
+/// How the table lists the entries, see [`ClickpackDb::with_view_mode`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ViewMode {
+    /// One row per entry.
+    #[default]
+    Flat,
+    /// One collapsible section per author. Falls back to [`ViewMode::Flat`]
+    /// if no entry has an author.
+    GroupedByAuthor,
+}
+
+impl DatabaseOrder {
+    const fn sort_order(self) -> Option<(SortColumn, bool)> {
+        match self {
+            Self::ByInsertionOrder => None,
+            Self::ByName => Some((SortColumn::Name, true)),
+            Self::BySize => Some((SortColumn::Size, true)),
+            Self::ByDownloads => Some((SortColumn::Downloads, false)),
+        }
+    }
+}
+
+/// Filters picked in the "Tags…" menu, kept between sessions in [`STATE_FILENAME`].
+#[derive(serde::Serialize, serde::Deserialize, Default, Clone, PartialEq)]
+#[serde(default)]
 struct Tags {
     noise: bool,
+    softclicks: bool,
+    hardclicks: bool,
     downloaded: bool,
+    favorites: bool,
+    featured: bool,
+    /// Only entries licensed under CC0.
+    cc0_only: bool,
+    /// User-defined tags that an entry must all have.
+    custom: HashSet<String>,
+    /// Official tags from the database that an entry must all have.
+    official: HashSet<String>,
+    /// Bots that an entry must all be compatible with.
+    compatible_with: HashSet<String>,
+    /// Minimum uncompressed size.
+    size_min_bytes: Option<usize>,
+    /// Maximum uncompressed size.
+    size_max_bytes: Option<usize>,
+    /// Minimum number of downloads.
+    min_downloads: Option<u32>,
+    /// Letter that entry names must start with, picked in the A–Z bar. Not
+    /// saved, since the bar is hidden after a restart.
+    #[serde(skip)]
+    name_starts_with: Option<char>,
 }
 
 impl Tags {
     #[inline]
-    const fn has_any(&self) -> bool {
-        self.noise || self.downloaded
+    fn has_any(&self) -> bool {
+        self.noise
+            || self.softclicks
+            || self.hardclicks
+            || self.downloaded
+            || self.favorites
+            || self.featured
+            || self.cc0_only
+            || !self.custom.is_empty()
+            || !self.official.is_empty()
+            || !self.compatible_with.is_empty()
+            || self.size_min_bytes.is_some()
+            || self.size_max_bytes.is_some()
+            || self.min_downloads.is_some()
+            || self.name_starts_with.is_some()
+    }
+}
+
+#[cfg(feature = "live")]
+#[derive(Clone)]
+enum PendingDelete {
+    Entry(String),
+    All,
+}
+
+/// State that is persisted between sessions in [`STATE_FILENAME`].
+#[derive(serde::Serialize, serde::Deserialize, Default, Clone)]
+struct PersistentState {
+    /// Maps clickpack names to the directories they were extracted to.
+    #[serde(default)]
+    downloaded: HashMap<String, PathBuf>,
+    /// `updated_at_unix` of the database that was last loaded.
+    #[serde(default)]
+    last_seen_updated_at: i64,
+    /// Sizes of the entries in the database that was last loaded.
+    #[serde(default)]
+    entry_sizes: HashMap<String, usize>,
+    /// Versions of the downloaded clickpacks, for entries that have one.
+    #[serde(default)]
+    downloaded_versions: HashMap<String, String>,
+    /// Names of the entries in the database that was last loaded.
+    #[serde(default)]
+    previously_seen_entries: HashSet<String>,
+    /// Filters that were active when the state was last saved.
+    #[serde(default)]
+    tags: Tags,
+}
+
+/// Everything [`ClickpackDb`] keeps between sessions, for apps that store it
+/// themselves. See [`ClickpackDb::serialize_state`].
+#[derive(serde::Serialize, serde::Deserialize, Default)]
+pub struct ClickpackDbState {
+    #[serde(flatten)]
+    state: PersistentState,
+    #[serde(default)]
+    favorites: HashSet<String>,
+    #[serde(default)]
+    custom_tags: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    local_overrides: HashMap<String, EntryOverrides>,
+    #[serde(default)]
+    search_query: String,
+}
+
+/// Changes the user made to an entry, on top of the values from the database.
+#[derive(serde::Serialize, serde::Deserialize, Default, Clone)]
+struct EntryOverrides {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    has_noise: Option<bool>,
+}
+
+impl EntryOverrides {
+    fn apply(&self, entry: &mut Entry) {
+        if let Some(has_noise) = self.has_noise {
+            entry.has_noise = has_noise;
+        }
+    }
+}
+
+/// A change in the download directory, found by [`ClickpackDb::watch_download_directory`].
+enum WatchEvent {
+    Created(PathBuf),
+    Removed(PathBuf),
+}
+
+impl WatchEvent {
+    /// Converts a `notify` event about the immediate children of `dir`.
+    fn from_notify(event: notify::Event, dir: &Path) -> impl Iterator<Item = Self> + '_ {
+        use notify::EventKind;
+        event
+            .paths
+            .into_iter()
+            .filter(move |path| path.parent() == Some(dir))
+            .filter_map(move |path| match event.kind {
+                EventKind::Create(_) if path.is_dir() => Some(Self::Created(path)),
+                EventKind::Remove(_) => Some(Self::Removed(path)),
+                // moved into or out of the directory
+                EventKind::Modify(notify::event::ModifyKind::Name(_)) => Some(if path.is_dir() {
+                    Self::Created(path)
+                } else {
+                    Self::Removed(path)
+                }),
+                _ => None,
+            })
     }
 }
 
+/// Downloads shown by [`ClickpackDbQueuePanel`], updated by [`ClickpackDb`] every frame.
+#[derive(Default)]
+struct QueueState {
+    /// Names and statuses of entries that are downloading or failed to download.
+    items: Vec<(String, DownloadStatus)>,
+}
+
 #[derive(Default)]
 pub struct ClickpackDb {
     pub status: Arc<RwLock<Status>>,
     pub db: Arc<RwLock<Database>>,
     filtered_entries: IndexMap<String, Entry>,
+    /// Copy of the entries on the current page of `filtered_entries`, so the
+    /// table doesn't look up and clone every row while rendering.
+    visible_entries: Vec<(String, Entry)>,
+    /// Bumped whenever `visible_entries` is rebuilt.
+    visible_generation: u64,
     search_query: String,
+    /// Whether the search query is matched case-sensitively.
+    case_sensitive: bool,
+    /// Last searched queries, most recent first.
+    search_history: std::collections::VecDeque<String>,
     pending_update: Arc<RwLock<IndexMap<String, Entry>>>,
     /// If [`Some`], this clickpack should be selected and the viewport should be closed.
     pub select_clickpack: Option<PathBuf>,
+    /// Name of the entry and the bytes of its [`Entry::preview_url`], set when
+    /// "▶ Preview" is clicked and the preview is fetched. The embedder should
+    /// play it and set this back to [`None`].
+    pub preview_audio: Option<PreviewAudio>,
+    /// Written by the thread that fetches the preview audio.
+    pending_preview_audio: Arc<Mutex<Option<PreviewAudio>>>,
+    /// Re-downloads that were cancelled or failed, whose previous version is
+    /// kept. Written by the download threads.
+    reverted_updates: Arc<Mutex<HashSet<String>>>,
     tags: Tags,
     pending_clickpack_delete: Vec<PathBuf>,
+    /// What the delete confirmation dialog is shown for.
+    #[cfg(feature = "live")]
+    pending_delete_confirm: Option<PendingDelete>,
+    /// Current sort column and whether it is ascending. [`None`] uses `default_order`.
+    sort_order: Option<(SortColumn, bool)>,
+    default_order: DatabaseOrder,
+    state: PersistentState,
+    /// Set by [`ClickpackDb::deserialize_state`], the state files and the
+    /// database cache aren't read or written then.
+    state_from_embedder: bool,
+    streaming_req_fn: Option<&'static StreamingRequestFn>,
+    /// Byte counts of in-flight downloads, written by the download threads.
+    download_progress: HashMap<String, DownloadProgress>,
+    /// Where each entry was last downloaded to, so that failed downloads are
+    /// retried into the same place.
+    download_targets: HashMap<String, DownloadTarget>,
+    download_slots: Arc<DownloadSlots>,
+    favorites: HashSet<String>,
+    /// User-defined tags, keyed by entry name.
+    custom_tags: HashMap<String, Vec<String>>,
+    /// User changes to entries, keyed by entry name.
+    local_overrides: HashMap<String, EntryOverrides>,
+    /// Names shown instead of the database keys, see [`ClickpackDb::set_display_name`].
+    display_name_overrides: HashMap<String, String>,
+    /// Entries hidden by the embedder, see [`ClickpackDb::block_entry`].
+    blocked_entries: HashSet<String>,
+    /// Filter set by the embedder, see [`ClickpackDb::set_custom_filter`].
+    custom_filter: Option<Arc<CustomFilterFn>>,
+    /// Names of the entries that share a download URL, keyed by the URL. Only
+    /// URLs with more than one entry are included.
+    shared_urls: HashMap<String, Vec<String>>,
+    /// Changes found by the download directory watcher, handled every frame.
+    watch_events: Arc<Mutex<Vec<WatchEvent>>>,
+    /// Watches the download directory, dropping it stops watching.
+    watcher: Option<notify::RecommendedWatcher>,
+    /// Whether the database changed since the previous session.
+    show_update_banner: bool,
+    /// Entries that are new or changed size since the previous session.
+    whats_new: Vec<String>,
+    /// Entries that weren't in the database in the previous session.
+    new_entries: HashSet<String>,
+    /// Entry that the table should scroll to on the next frame.
+    scroll_to_entry: Option<String>,
+    view_mode: ViewMode,
+    /// Download count from which entry names are highlighted, see
+    /// [`ClickpackDb::with_popular_threshold`].
+    popular_threshold: Option<u32>,
+    /// Whether popular entries are highlighted, toggled in the tags menu.
+    highlight_popular: bool,
+    /// Authors whose section is expanded in [`ViewMode::GroupedByAuthor`].
+    expanded_groups: HashSet<String>,
+    /// When a refresh last finished successfully, see [`ClickpackDb::last_refresh_time`].
+    last_refresh_time: Option<std::time::Instant>,
+    /// The database is being loaded again by [`ClickpackDb::force_refresh`].
+    is_refreshing: bool,
+    /// When the search query last changed without filtering the table yet,
+    /// see [`ClickpackDb::with_search_debounce_ms`].
+    last_search_change: Option<std::time::Instant>,
+    /// Whether the A–Z bar is shown above the table.
+    show_alphabet_bar: bool,
+    /// Whether the table is scrolled down far enough to show the "⬆ Top" button.
+    show_scroll_to_top: bool,
+    /// Whether the table should scroll back to the first row on the next frame.
+    scroll_to_top: bool,
+    /// Current page of the table, starting at 0.
+    page: usize,
+    /// Entry that the suggestions were computed for, i.e. the last selected one.
+    selected_for_suggestion: Option<String>,
+    /// Entries similar to `selected_for_suggestion`.
+    suggestions: Vec<String>,
+    /// How many URLs "Export URLs" copied, and until when to say so.
+    copy_ack_until: Option<(usize, std::time::Instant)>,
+    /// Entry that was jumped to, and when.
+    highlighted_entry: Option<(String, std::time::Instant)>,
+    /// Entries whose description is expanded below their name.
+    expanded_entries: BTreeSet<String>,
+    /// Text of the "Add tag…" input in the context menu.
+    new_tag_input: String,
+    /// Called with the path of a click sound when the "▶" button of a downloaded
+    /// clickpack is clicked. The button is hidden when this is [`None`].
+    pub preview_callback: Option<Box<dyn Fn(PathBuf)>>,
+    /// Called when a clickpack is selected, see [`ClickpackDb::on_select`].
+    on_select: Option<Box<dyn Fn(PathBuf)>>,
+    /// Called when a download finishes, see [`ClickpackDb::on_download_complete`].
+    on_download_complete: Option<Box<DownloadCompleteFn>>,
+    /// Queues of the live subscriptions, see [`ClickpackDb::subscribe`].
+    subscriptions: Vec<std::sync::Weak<EventQueue>>,
+    /// Embedder-defined columns, see [`ClickpackDb::with_extra_columns`].
+    extra_columns: Vec<ExtraColumn>,
+    theme: ClickpackDbTheme,
+    /// Shared with the panels returned by [`ClickpackDb::queue_panel`].
+    queue: Arc<RwLock<QueueState>>,
+    config: ClickpackDbConfig,
+    /// Set by [`ClickpackDb::load_from_path`], replaces the network database.
+    local_db_path: Option<PathBuf>,
+    /// Set by [`ClickpackDb::set_download_directory`], replaces the temp directory.
+    #[cfg(not(feature = "live"))]
+    download_directory: Option<PathBuf>,
     #[cfg(feature = "live")]
     pub has_refreshed: bool,
 }
 
-#[cfg(not(feature = "live"))]
-pub fn cleanup() {
-    log::info!("cleaning up temp directories...");
-    let mut temp_dir = std::env::temp_dir();
-    if temp_dir.try_exists().unwrap_or(false) {
-        temp_dir.push(TEMP_DIRNAME);
+/// Renders a cell of an [`ExtraColumn`], given the name of the entry and the entry.
+type ExtraColumnRenderFn = dyn Fn(&mut egui::Ui, &str, &Entry);
+
+/// Table column defined by the embedder, shown after the standard columns.
+/// See [`ClickpackDb::with_extra_columns`].
+pub struct ExtraColumn {
+    pub header: String,
+    pub width: f32,
+    pub render: Box<ExtraColumnRenderFn>,
+}
+
+impl ExtraColumn {
+    pub fn new(
+        header: impl Into<String>,
+        width: f32,
+        render: impl Fn(&mut egui::Ui, &str, &Entry) + 'static,
+    ) -> Self {
+        Self {
+            header: header.into(),
+            width,
+            render: Box::new(render),
+        }
+    }
+}
+
+/// Something that happened in a [`ClickpackDb`], see [`ClickpackDb::subscribe`].
+#[derive(Clone, Debug)]
+pub enum ClickpackDbEvent {
+    /// The database was loaded or refreshed.
+    DatabaseLoaded,
+    /// A download of the named entry started.
+    DownloadStarted(String),
+    /// The named entry finished downloading into the directory.
+    DownloadComplete(String, PathBuf),
+    /// The named entry failed to download, with the error.
+    DownloadFailed(String, String),
+    /// A clickpack was selected.
+    SelectPack(PathBuf),
+}
+
+type EventQueue = Mutex<VecDeque<ClickpackDbEvent>>;
+
+/// Receives the events of a [`ClickpackDb`], see [`ClickpackDb::subscribe`].
+/// Events are no longer queued once this is dropped.
+pub struct ClickpackDbSubscription {
+    events: Arc<EventQueue>,
+}
+
+impl ClickpackDbSubscription {
+    /// Takes all events that happened since the last call, oldest first.
+    pub fn drain(&self) -> Vec<ClickpackDbEvent> {
+        self.events.lock().unwrap().drain(..).collect()
+    }
+}
+
+/// Colors of the tags and status labels, see [`ClickpackDb::with_theme`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ClickpackDbTheme {
+    pub noise_color: Color32,
+    pub downloaded_color: Color32,
+    pub error_color: Color32,
+    /// Color of the badge on entries that are new since the previous session.
+    pub new_badge_color: Color32,
+}
+
+impl Default for ClickpackDbTheme {
+    fn default() -> Self {
+        Self {
+            noise_color: Color32::KHAKI,
+            downloaded_color: Color32::LIGHT_GREEN,
+            error_color: Color32::RED,
+            new_badge_color: Color32::ORANGE,
+        }
+    }
+}
+
+/// Configuration for [`ClickpackDb::new_with_config`].
+#[derive(Clone, Debug)]
+pub struct ClickpackDbConfig {
+    database_url: String,
+    #[cfg(not(feature = "live"))]
+    temp_dirname: String,
+    max_concurrent_downloads: usize,
+    page_size: usize,
+    cache_ttl_secs: u64,
+    show_export_button: bool,
+    row_height_multiplier: f32,
+    search_debounce_ms: u64,
+    name_column_width: f32,
+    /// Width of the column with the download buttons, [`None`] to fit them.
+    actions_column_width: Option<f32>,
+    max_download_bps: Option<usize>,
+}
+
+impl Default for ClickpackDbConfig {
+    fn default() -> Self {
+        Self {
+            database_url: DATABASE_URL.to_string(),
+            #[cfg(not(feature = "live"))]
+            temp_dirname: TEMP_DIRNAME.to_string(),
+            max_concurrent_downloads: DEFAULT_MAX_CONCURRENT_DOWNLOADS,
+            page_size: DEFAULT_PAGE_SIZE,
+            cache_ttl_secs: DEFAULT_CACHE_TTL_SECS,
+            show_export_button: false,
+            row_height_multiplier: DEFAULT_ROW_HEIGHT_MULTIPLIER,
+            search_debounce_ms: 0,
+            name_column_width: NAME_COLUMN_WIDTH,
+            actions_column_width: None,
+            max_download_bps: None,
+        }
+    }
+}
+
+impl ClickpackDbConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// URL of the `db.json` to load, e.g. a private mirror of the database.
+    pub fn database_url(mut self, url: impl Into<String>) -> Self {
+        self.database_url = url.into();
+        self
+    }
+
+    /// Name of the directory inside [`std::env::temp_dir`] that clickpacks
+    /// and the widget's own files are stored in.
+    #[cfg(not(feature = "live"))]
+    pub fn temp_dirname(mut self, dirname: impl Into<String>) -> Self {
+        self.temp_dirname = dirname.into();
+        self
+    }
+
+    /// How many clickpacks can be downloaded at the same time.
+    pub fn max_concurrent_downloads(mut self, n: usize) -> Self {
+        self.max_concurrent_downloads = n;
+        self
+    }
+
+    /// How many rows the table shows per page.
+    pub fn page_size(mut self, n: usize) -> Self {
+        self.page_size = n;
+        self
+    }
+
+    /// Whether to show an "Export URLs" button that copies the URLs of all
+    /// visible entries, one per line (default: `false`).
+    pub fn show_export_button(mut self, show: bool) -> Self {
+        self.show_export_button = show;
+        self
+    }
+
+    /// Height of the table rows relative to the text height, clamped to
+    /// `1.0..=4.0` (default: `1.5`).
+    pub fn row_height_multiplier(mut self, f: f32) -> Self {
+        self.row_height_multiplier = f.clamp(1.0, 4.0);
+        self
+    }
+
+    /// Widths of the name column (default: `200.0`) and of the column with the
+    /// download buttons (default: [`None`], as wide as the buttons), e.g. to
+    /// fit the table into a narrow panel.
+    pub fn column_widths(mut self, name_width: f32, actions_width: Option<f32>) -> Self {
+        self.name_column_width = name_width;
+        self.actions_column_width = actions_width;
+        self
+    }
+
+    /// How long to wait after the last keystroke in the search bar before
+    /// filtering the table, in milliseconds (default: `0`, filter immediately).
+    pub fn search_debounce_ms(mut self, ms: u64) -> Self {
+        self.search_debounce_ms = ms;
+        self
+    }
+
+    /// Limits each clickpack download to `bps` bytes per second (default:
+    /// [`None`], unlimited). Only downloads through the streaming request
+    /// function are limited, see [`ClickpackDb::set_streaming_req_fn`].
+    pub fn max_download_bps(mut self, bps: Option<usize>) -> Self {
+        self.max_download_bps = bps.filter(|&bps| bps > 0);
+        self
+    }
+
+    /// How old the cached database can be before it is no longer used when the
+    /// network is unavailable (default: one day).
+    pub fn cache_ttl_secs(mut self, secs: u64) -> Self {
+        self.cache_ttl_secs = secs;
+        self
+    }
+
+    #[cfg(not(feature = "live"))]
+    fn temp_dir(&self) -> PathBuf {
+        std::env::temp_dir().join(&self.temp_dirname)
+    }
+
+    /// Directory where the widget keeps its own files, like [`STATE_FILENAME`].
+    fn state_dir(&self) -> PathBuf {
+        #[cfg(not(feature = "live"))]
+        {
+            self.temp_dir()
+        }
+        #[cfg(feature = "live")]
+        {
+            PathBuf::from(".zcb").join("clickpackdb")
+        }
+    }
+
+    /// Removes the temp directory, including downloaded clickpacks and the state sidecar.
+    #[cfg(not(feature = "live"))]
+    pub fn cleanup(&self) {
+        log::info!("cleaning up temp directories...");
+        let temp_dir = self.temp_dir();
         if temp_dir.try_exists().unwrap_or(false) {
             let _ = std::fs::remove_dir_all(temp_dir)
                 .map_err(|e| log::error!("remove_dir_all failed: {e}"));
         }
+    }
+}
+
+/// Total size of all files in a directory, recursively.
+fn dir_size(path: &Path) -> usize {
+    let Ok(read_dir) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    read_dir
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len() as usize,
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// First click sound (`.ogg`, `.wav` or `.mp3`) in a clickpack directory, recursively.
+/// Noise files are skipped.
+fn find_preview_sound(path: &Path) -> Option<PathBuf> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(path)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .collect();
+    paths.sort();
+    let (dirs, files): (Vec<_>, Vec<_>) = paths.into_iter().partition(|path| path.is_dir());
+    files
+        .into_iter()
+        .find(|path| {
+            let is_sound = path.extension().is_some_and(|ext| {
+                ["ogg", "wav", "mp3"]
+                    .iter()
+                    .any(|sound_ext| ext.eq_ignore_ascii_case(sound_ext))
+            });
+            let is_noise = path
+                .file_stem()
+                .is_some_and(|stem| stem.to_string_lossy().to_lowercase().contains("noise"));
+            is_sound && !is_noise
+        })
+        .or_else(|| dirs.iter().find_map(|dir| find_preview_sound(dir)))
+}
+
+/// Reads the cached `db.json` and when it was written, unless it is older than `ttl_secs`.
+fn read_db_cache(path: &Path, ttl_secs: u64) -> Option<(Vec<u8>, i64)> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    if modified.elapsed().unwrap_or_default() > std::time::Duration::from_secs(ttl_secs) {
+        log::info!("database cache {path:?} is too old, ignoring it");
+        return None;
+    }
+    let cached_at_unix = modified
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64);
+    Some((std::fs::read(path).ok()?, cached_at_unix))
+}
+
+fn read_state_file<T: serde::de::DeserializeOwned + Default>(dir: &Path, filename: &str) -> T {
+    let path = dir.join(filename);
+    match std::fs::read(&path) {
+        Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+            log::error!("failed to parse {path:?}: {e}");
+            T::default()
+        }),
+        Err(_) => T::default(),
+    }
+}
+
+fn write_state_file<T: serde::Serialize>(dir: &Path, filename: &str, value: &T) {
+    if let Err(e) = std::fs::create_dir_all(dir) {
+        log::error!("failed to create state directory {dir:?}: {e}");
+        return;
+    }
+    let path = dir.join(filename);
+    match serde_json::to_vec(value) {
+        Ok(bytes) => {
+            if let Err(e) = std::fs::write(&path, bytes) {
+                log::error!("failed to write {path:?}: {e}");
+            }
+        }
+        Err(e) => log::error!("failed to serialize {filename}: {e}"),
+    }
+}
+
+/// Removes the default temp directory, including downloaded clickpacks and the
+/// state sidecar. Use [`ClickpackDbConfig::cleanup`] if a custom directory is configured.
+#[cfg(not(feature = "live"))]
+pub fn cleanup() {
+    ClickpackDbConfig::default().cleanup();
+}
+
+/// Fetches and parses the database on a background thread, e.g. while a splash
+/// screen is shown. Pass the result to [`ClickpackDb::new_with_database`].
+pub fn prefetch_database(
+    req_fn: &'static RequestFn,
+) -> std::thread::JoinHandle<Result<Database, String>> {
+    prefetch_database_with_config(req_fn, &ClickpackDbConfig::default())
+}
+
+/// Like [`prefetch_database`], but fetches [`ClickpackDbConfig::database_url`],
+/// e.g. a private mirror. Pass the result and the same config to
+/// [`ClickpackDb::new_with_database_and_config`].
+pub fn prefetch_database_with_config(
+    req_fn: &'static RequestFn,
+    config: &ClickpackDbConfig,
+) -> std::thread::JoinHandle<Result<Database, String>> {
+    let url = config.database_url.clone();
+    log::info!("prefetching database from {url}");
+    std::thread::spawn(move || {
+        let body = req_fn(&url)?;
+        serde_json::from_slice(&body).map_err(|e| e.to_string())
+    })
+}
+
+/// Wraps a request function that accepts headers into a regular request function
+/// that always sends `headers`, e.g. an `Authorization` header for a private
+/// database mirror. The returned function is leaked, so this should only be
+/// called once.
+pub fn make_req_fn_with_headers(
+    inner: &'static RequestWithHeadersFn,
+    headers: HashMap<String, String>,
+) -> &'static RequestFn {
+    Box::leak(Box::new(move |url: &str| inner(url, &headers)))
+}
+
+/// Creates a request function that answers with `responses`, keyed by URL,
+/// instead of using the network, e.g. for headless tests together with
+/// [`ClickpackDb::new_with_mock_db`]. Unknown URLs return an error. The
+/// returned function is leaked, like in [`make_req_fn_with_headers`].
+pub fn mock_req_fn(responses: HashMap<String, Vec<u8>>) -> &'static RequestFn {
+    Box::leak(Box::new(move |url: &str| {
+        responses
+            .get(url)
+            .cloned()
+            .ok_or_else(|| format!("no mock response for {url}"))
+    }))
+}
+
+/// A filter written in the search bar, like `noise:yes`, `size:>5mb` or
+/// `downloads:>1000`. The bounds are inclusive.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum SearchToken {
+    HasNoise(bool),
+    MinSize(usize),
+    MaxSize(usize),
+    MinDownloads(u32),
+    MaxDownloads(u32),
+}
+
+impl SearchToken {
+    fn matches(self, entry: &Entry) -> bool {
+        match self {
+            Self::HasNoise(has_noise) => entry.has_noise == has_noise,
+            Self::MinSize(min) => entry.size >= min,
+            Self::MaxSize(max) => entry.size <= max,
+            Self::MinDownloads(min) => entry.downloads >= min,
+            Self::MaxDownloads(max) => entry.downloads <= max,
+        }
+    }
+
+    fn parse(word: &str) -> Option<Self> {
+        let (key, value) = word.split_once(':')?;
+        let value = value.to_ascii_lowercase();
+        match key.to_ascii_lowercase().as_str() {
+            "noise" => match value.as_str() {
+                "yes" | "y" | "true" | "1" => Some(Self::HasNoise(true)),
+                "no" | "n" | "false" | "0" => Some(Self::HasNoise(false)),
+                _ => None,
+            },
+            "size" => match parse_bound(&value, parse_size)? {
+                (true, size) => Some(Self::MinSize(size)),
+                (false, size) => Some(Self::MaxSize(size)),
+            },
+            "downloads" => {
+                let parse_count = |count: &str| count.trim().parse().ok();
+                match parse_bound(&value, parse_count)? {
+                    (true, count) => Some(Self::MinDownloads(count)),
+                    (false, count) => Some(Self::MaxDownloads(count)),
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Parses a comparison like `>=5`, `>5`, `<=5` or `<5` into an inclusive bound
+/// and whether it is a minimum, e.g. `>5` is `(true, 6)`.
+fn parse_bound<T: TryFrom<usize>>(
+    value: &str,
+    parse: impl Fn(&str) -> Option<usize>,
+) -> Option<(bool, T)> {
+    let (is_min, bound) = if let Some(n) = value.strip_prefix(">=") {
+        (true, parse(n)?)
+    } else if let Some(n) = value.strip_prefix('>') {
+        (true, parse(n)?.checked_add(1)?)
+    } else if let Some(n) = value.strip_prefix("<=") {
+        (false, parse(n)?)
+    } else if let Some(n) = value.strip_prefix('<') {
+        (false, parse(n)?.checked_sub(1)?)
+    } else {
+        return None;
     };
+    Some((is_min, T::try_from(bound).ok()?))
+}
+
+/// Parses sizes like `5mb`, `500kb` or `1.5gb` (decimal units) into bytes.
+fn parse_size(size: &str) -> Option<usize> {
+    let size = size.trim();
+    let (number, multiplier) = [("gb", 1e9), ("mb", 1e6), ("kb", 1e3), ("b", 1.0)]
+        .into_iter()
+        .find_map(|(unit, multiplier)| Some((size.strip_suffix(unit)?, multiplier)))
+        .unwrap_or((size, 1.0));
+    let number: f64 = number.trim().parse().ok()?;
+    (number >= 0.0).then_some((number * multiplier) as usize)
+}
+
+/// Splits the search tokens out of a search query. Returns the tokens and the
+/// rest of the query, which is used for fuzzy matching.
+fn parse_search_tokens(query: &str) -> (Vec<SearchToken>, String) {
+    let mut tokens = vec![];
+    let mut rest = vec![];
+    for word in query.split_whitespace() {
+        match SearchToken::parse(word) {
+            Some(token) => tokens.push(token),
+            None => rest.push(word),
+        }
+    }
+    (tokens, rest.join(" "))
+}
+
+/// Computes the SHA-256 of `data` as a lowercase hex string.
+fn sha256_hex(data: &[u8]) -> String {
+    use sha2::Digest;
+    sha2::Sha256::digest(data)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Quotes a CSV field as described in RFC 4180 if it needs quoting.
+fn csv_field(field: &str) -> std::borrow::Cow<'_, str> {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\"")).into()
+    } else {
+        field.into()
+    }
+}
+
+/// Seconds elapsed since the given unix timestamp.
+fn secs_since_unix(unix: i64) -> i64 {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64);
+    (now - unix).max(0)
+}
+
+/// Formats the age of the database, e.g. "just now", "3 hours ago", "2 days ago".
+fn format_db_age(unix: i64) -> String {
+    let plural = |n: i64, unit: &str| format!("{n} {unit}{} ago", if n == 1 { "" } else { "s" });
+    match secs_since_unix(unix) {
+        secs if secs < 60 => "just now".to_string(),
+        secs if secs < 60 * 60 => plural(secs / 60, "minute"),
+        secs if secs < 60 * 60 * 24 => plural(secs / (60 * 60), "hour"),
+        secs => plural(secs / (60 * 60 * 24), "day"),
+    }
 }
 
 fn tag_text(ui: &mut egui::Ui, color: Color32, emote: &str, text: &str) -> egui::WidgetText {
@@ -136,186 +1274,2739 @@ fn tag_text(ui: &mut egui::Ui, color: Color32, emote: &str, text: &str) -> egui:
 }
 
 impl ClickpackDb {
-    fn load_database(
-        status: Arc<RwLock<Status>>,
-        db: Arc<RwLock<Database>>,
-        req_fn: &'static RequestFn,
-    ) {
-        log::info!("loading database from {DATABASE_URL}");
-        std::thread::spawn(move || match req_fn(DATABASE_URL) {
+    pub fn new_with_config(config: ClickpackDbConfig) -> Self {
+        let db = Self {
+            config,
+            ..Default::default()
+        };
+        db.download_slots
+            .set_limit(db.config.max_concurrent_downloads);
+        db
+    }
+
+    /// Creates the widget with a database parsed from `db_json`, in the
+    /// `db.json` format, without fetching anything. Meant for tests, see
+    /// [`mock_req_fn`] for mocking the downloads as well.
+    pub fn new_with_mock_db(db_json: &[u8]) -> Result<Self, serde_json::Error> {
+        Ok(Self::new_with_database(serde_json::from_slice(db_json)?))
+    }
+
+    /// Creates the widget with an already loaded database, e.g. one returned by
+    /// [`prefetch_database`]. The initial load is skipped.
+    pub fn new_with_database(db: Database) -> Self {
+        Self::new_with_database_and_config(db, ClickpackDbConfig::default())
+    }
+
+    /// Like [`ClickpackDb::new_with_database`], but with a config like in
+    /// [`ClickpackDb::new_with_config`], e.g. for a database prefetched with
+    /// [`prefetch_database_with_config`].
+    pub fn new_with_database_and_config(db: Database, config: ClickpackDbConfig) -> Self {
+        log::info!(
+            "using prefetched database with {} entries",
+            db.entries.len()
+        );
+        Self {
+            status: Arc::new(RwLock::new(Status::Loaded { did_filter: false })),
+            db: Arc::new(RwLock::new(db)),
+            ..Self::new_with_config(config)
+        }
+    }
+
+    /// Whether the database hasn't been loaded yet or is being loaded.
+    pub fn is_loading(&self) -> bool {
+        matches!(
+            *self.status.read().unwrap(),
+            Status::NotLoaded | Status::Loading
+        )
+    }
+
+    /// Whether the database is loaded, either from the network or from the cache.
+    pub fn is_ready(&self) -> bool {
+        matches!(
+            *self.status.read().unwrap(),
+            Status::Loaded { .. } | Status::LoadedFromCache { .. }
+        )
+    }
+
+    /// Whether the database failed to load.
+    pub fn has_error(&self) -> bool {
+        matches!(*self.status.read().unwrap(), Status::Error(_))
+    }
+
+    /// Short human-readable summary of what the widget is doing, e.g.
+    /// `"3 packs downloading…"` or `"DB loaded (412 packs)"`, for window titles.
+    /// Ends with the name of the selected clickpack, if any.
+    pub fn get_status_summary(&self) -> String {
+        let status = self.status.read().unwrap().clone();
+        let (nr_packs, nr_downloading, selected) = {
+            let db = self.db.read().unwrap();
+            let nr_downloading = db
+                .entries
+                .values()
+                .filter(|e| e.dwn_status.is_in_progress())
+                .count();
+            let selected = self.select_clickpack.as_deref().and_then(|path| {
+                db.entries
+                    .iter()
+                    .find(|(_, e)| e.downloaded_path() == Some(path))
+                    .map(|(name, _)| self.display_name(name))
+            });
+            (db.entries.len(), nr_downloading, selected)
+        };
+        let plural = |n: usize| if n == 1 { "pack" } else { "packs" };
+        let summary = match status {
+            Status::NotLoaded | Status::Loading => "Loading database…".to_string(),
+            Status::Error(e) => format!("Failed to load database: {e}"),
+            _ if nr_downloading > 0 => {
+                format!("{nr_downloading} {} downloading…", plural(nr_downloading))
+            }
+            Status::Loaded { .. } => format!("DB loaded ({nr_packs} {})", plural(nr_packs)),
+            Status::LoadedFromCache { .. } => {
+                format!("DB loaded from cache ({nr_packs} {})", plural(nr_packs))
+            }
+        };
+        match selected {
+            Some(name) => format!("{summary} — {name}"),
+            None => summary,
+        }
+    }
+
+    /// Serializes the state that is kept between sessions (downloaded clickpacks,
+    /// favorites, tags and the search query) as JSON, for apps that store it
+    /// themselves, e.g. in `eframe::Storage`.
+    pub fn serialize_state(&self) -> Vec<u8> {
+        let state = ClickpackDbState {
+            state: self.state.clone(),
+            favorites: self.favorites.clone(),
+            custom_tags: self.custom_tags.clone(),
+            local_overrides: self.local_overrides.clone(),
+            search_query: self.search_query.clone(),
+        };
+        serde_json::to_vec(&state).unwrap_or_else(|e| {
+            log::error!("failed to serialize state: {e}");
+            vec![]
+        })
+    }
+
+    /// Restores state written by [`ClickpackDb::serialize_state`]. After this,
+    /// the state files in the widget's own directory are no longer read, so this
+    /// should be called before the database is loaded.
+    pub fn deserialize_state(&mut self, bytes: &[u8]) -> Result<(), serde_json::Error> {
+        let ClickpackDbState {
+            state,
+            favorites,
+            custom_tags,
+            local_overrides,
+            search_query,
+        } = serde_json::from_slice(bytes)?;
+        self.tags = state.tags.clone();
+        self.state = state;
+        self.favorites = favorites;
+        self.custom_tags = custom_tags;
+        self.local_overrides = local_overrides;
+        self.search_query = search_query;
+        self.state_from_embedder = true;
+        if self.is_ready() {
+            self.apply_download_state();
+        }
+        self.update_filtered_entries();
+        Ok(())
+    }
+
+    /// Sets the search query, e.g. from a command line argument. Can be called
+    /// before the database is loaded, the query is applied once it is.
+    pub fn set_search_query(&mut self, query: &str) {
+        query.clone_into(&mut self.search_query);
+        self.page = 0;
+        self.update_filtered_entries();
+    }
+
+    /// Shows `display` instead of the name of an entry, e.g. to localize or
+    /// expand a cryptic name. The name is still used as the key everywhere else.
+    pub fn set_display_name(&mut self, key: &str, display: &str) {
+        self.display_name_overrides
+            .insert(key.to_string(), display.to_string());
+    }
+
+    /// Name of an entry as shown in the UI.
+    fn display_name(&self, name: &str) -> String {
+        self.display_name_overrides
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| name.replace('_', " "))
+    }
+
+    /// Hides an entry from the table, regardless of the search query and tags.
+    pub fn block_entry(&mut self, name: impl Into<String>) {
+        self.blocked_entries.insert(name.into());
+        self.update_filtered_entries();
+    }
+
+    /// Shows an entry that was hidden with [`ClickpackDb::block_entry`] again.
+    pub fn unblock_entry(&mut self, name: &str) {
+        if self.blocked_entries.remove(name) {
+            self.update_filtered_entries();
+        }
+    }
+
+    /// Only shows entries for which `filter` returns `true`, on top of the search
+    /// query and tags. For app-specific filters that [`ClickpackDb`] has no
+    /// setting for. Replaces the previous filter.
+    pub fn set_custom_filter(
+        &mut self,
+        filter: impl Fn(&str, &Entry) -> bool + Send + Sync + 'static,
+    ) {
+        self.custom_filter = Some(Arc::new(filter));
+        self.update_filtered_entries();
+    }
+
+    /// Removes the filter set with [`ClickpackDb::set_custom_filter`].
+    pub fn clear_custom_filter(&mut self) {
+        if self.custom_filter.take().is_some() {
+            self.update_filtered_entries();
+        }
+    }
+
+    /// Names of the entries hidden with [`ClickpackDb::block_entry`].
+    pub fn blocked_entries(&self) -> &HashSet<String> {
+        &self.blocked_entries
+    }
+
+    /// Number of entries in the database. O(1).
+    pub fn entry_count(&self) -> usize {
+        self.db.read().unwrap().entries.len()
+    }
+
+    /// Number of entries currently shown in the table. O(1).
+    pub fn filtered_count(&self) -> usize {
+        self.filtered_entries.len()
+    }
+
+    /// Number of downloaded entries in the database. O(n).
+    pub fn downloaded_count(&self) -> usize {
+        self.db
+            .read()
+            .unwrap()
+            .entries
+            .values()
+            .filter(|e| matches!(e.dwn_status, DownloadStatus::Downloaded { .. }))
+            .count()
+    }
+
+    /// Entries that are currently shown in the table, after filtering and sorting.
+    pub fn filtered_entries(&self) -> impl Iterator<Item = (&str, &Entry)> {
+        self.filtered_entries
+            .iter()
+            .map(|(name, entry)| (name.as_str(), entry))
+    }
+
+    /// Looks up an entry in the database by name. Returns a copy, since the
+    /// database is shared with the loading thread.
+    pub fn entry(&self, name: &str) -> Option<Entry> {
+        self.db.read().unwrap().entries.get(name).cloned()
+    }
+
+    /// Looks up an entry by its exact database key, without any fuzzy
+    /// matching. O(1). Same as [`ClickpackDb::entry`].
+    pub fn find_by_name_exact(&self, name: &str) -> Option<Entry> {
+        self.entry(name)
+    }
+
+    /// Finds the entry whose ZIP file is at `url`, returning its name and a
+    /// copy of it. O(n). Returns owned values, since the database is shared
+    /// with the loading thread and can't be borrowed past this call.
+    pub fn find_by_url(&self, url: &str) -> Option<(String, Entry)> {
+        self.db
+            .read()
+            .unwrap()
+            .entries
+            .iter()
+            .find(|(_, entry)| entry.url == url)
+            .map(|(name, entry)| (name.clone(), entry.clone()))
+    }
+
+    /// Copies of all entries in the database, most downloaded first. Entries
+    /// with the same download count keep their database order. O(n log n).
+    pub fn get_all_entries(&self) -> Vec<(String, Entry)> {
+        self.sorted_entries(|_| true)
+    }
+
+    /// Like [`ClickpackDb::get_all_entries`], but only with the downloaded
+    /// entries. O(n log n).
+    pub fn get_downloaded_entries(&self) -> Vec<(String, Entry)> {
+        self.sorted_entries(|e| matches!(e.dwn_status, DownloadStatus::Downloaded { .. }))
+    }
+
+    /// Clones the entries matching `filter`, sorted by downloads descending.
+    fn sorted_entries(&self, filter: impl Fn(&Entry) -> bool) -> Vec<(String, Entry)> {
+        let mut entries: Vec<(String, Entry)> = self
+            .db
+            .read()
+            .unwrap()
+            .entries
+            .iter()
+            .filter(|(_, entry)| filter(entry))
+            .map(|(name, entry)| (name.clone(), entry.clone()))
+            .collect();
+        entries.sort_by_key(|(_, entry)| std::cmp::Reverse(entry.downloads));
+        entries
+    }
+
+    /// Returns a handle that receives events like finished downloads, to react
+    /// to them instead of polling. Drain it every frame.
+    pub fn subscribe(&mut self) -> ClickpackDbSubscription {
+        let events = Arc::new(EventQueue::default());
+        self.subscriptions.push(Arc::downgrade(&events));
+        ClickpackDbSubscription { events }
+    }
+
+    /// Queues an event in all live subscriptions and forgets dropped ones.
+    fn emit(&mut self, event: ClickpackDbEvent) {
+        self.subscriptions.retain(|events| match events.upgrade() {
+            Some(events) => {
+                events.lock().unwrap().push_back(event.clone());
+                true
+            }
+            None => false,
+        });
+    }
+
+    /// Registers a callback that is called when a clickpack is selected, in
+    /// addition to setting [`ClickpackDb::select_clickpack`].
+    pub fn on_select(&mut self, callback: impl Fn(PathBuf) + 'static) {
+        self.on_select = Some(Box::new(callback));
+    }
+
+    /// Builder version of [`ClickpackDb::on_select`].
+    pub fn with_on_select(mut self, callback: impl Fn(PathBuf) + 'static) -> Self {
+        self.on_select(callback);
+        self
+    }
+
+    /// Adds columns with custom metadata (e.g. BPM) after the standard columns
+    /// of the table.
+    pub fn with_extra_columns(mut self, columns: Vec<ExtraColumn>) -> Self {
+        self.extra_columns = columns;
+        self
+    }
+
+    /// Sets the order of the table when the user hasn't picked a column to sort by.
+    pub fn with_default_order(mut self, order: DatabaseOrder) -> Self {
+        self.default_order = order;
+        self
+    }
+
+    /// Sets how the table lists the entries (default: [`ViewMode::Flat`]).
+    pub fn with_view_mode(mut self, view_mode: ViewMode) -> Self {
+        self.view_mode = view_mode;
+        self
+    }
+
+    /// Highlights the names of entries with at least `n` downloads. The
+    /// highlighting can be turned off in the tags menu.
+    pub fn with_popular_threshold(mut self, n: u32) -> Self {
+        self.popular_threshold = Some(n);
+        self.highlight_popular = true;
+        self
+    }
+
+    /// Sets the colors of the tags and status labels.
+    pub fn with_theme(mut self, theme: ClickpackDbTheme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Creates a panel that lists all in-progress and failed downloads, so they
+    /// can be seen without scrolling to their rows.
+    pub fn queue_panel(&self) -> ClickpackDbQueuePanel {
+        ClickpackDbQueuePanel {
+            queue: self.queue.clone(),
+            theme: self.theme,
+        }
+    }
+
+    /// Registers a callback that is called with the name and directory of a
+    /// clickpack when it finishes downloading and extracting.
+    pub fn on_download_complete(&mut self, callback: impl Fn(&str, &PathBuf) + 'static) {
+        self.on_download_complete = Some(Box::new(callback));
+    }
+
+    /// Builder version of [`ClickpackDb::on_download_complete`].
+    pub fn with_on_download_complete(
+        mut self,
+        callback: impl Fn(&str, &PathBuf) + 'static,
+    ) -> Self {
+        self.on_download_complete(callback);
+        self
+    }
+
+    /// Sets how many rows the table shows per page (default: 50).
+    pub fn with_page_size(mut self, n: usize) -> Self {
+        self.config.page_size = n;
+        self
+    }
+
+    /// Sets the height of the table rows relative to the text height, e.g. for
+    /// taller rows on high-DPI displays. Clamped to `1.0..=4.0` (default: `1.5`).
+    pub fn with_row_height_multiplier(mut self, f: f32) -> Self {
+        self.config.row_height_multiplier = f.clamp(1.0, 4.0);
+        self
+    }
+
+    /// Sets the widths of the name column (default: `200.0`) and of the column
+    /// with the download buttons (default: [`None`], as wide as the buttons),
+    /// e.g. to fit the table into a narrow panel.
+    pub fn with_column_widths(mut self, name_width: f32, actions_width: Option<f32>) -> Self {
+        self.config = self.config.column_widths(name_width, actions_width);
+        self
+    }
+
+    /// Waits until the search query hasn't changed for `ms` milliseconds before
+    /// filtering the table, instead of filtering on every keystroke. Useful
+    /// for large databases (default: `0`).
+    pub fn with_search_debounce_ms(mut self, ms: u64) -> Self {
+        self.config.search_debounce_ms = ms;
+        self
+    }
+
+    /// Limits each clickpack download to `bps` bytes per second, e.g. for
+    /// metered connections (default: [`None`]). Only works with a streaming
+    /// request function, see [`ClickpackDb::set_streaming_req_fn`].
+    pub fn with_max_download_bps(mut self, bps: Option<usize>) -> Self {
+        self.config = self.config.max_download_bps(bps);
+        self
+    }
+
+    /// Sets how many clickpacks can be downloaded at the same time (default: 3).
+    /// Downloads started past this limit wait until a slot is free.
+    pub fn with_max_concurrent_downloads(mut self, n: usize) -> Self {
+        self.config.max_concurrent_downloads = n;
+        self.download_slots.set_limit(n);
+        self
+    }
+
+    /// Fetches the database in the background. [`DB_CACHE_FILENAME`] is only
+    /// written and used as a fallback if `use_cache` is set.
+    fn load_database(
+        status: Arc<RwLock<Status>>,
+        db: Arc<RwLock<Database>>,
+        req_fn: &'static RequestFn,
+        url: String,
+        config: ClickpackDbConfig,
+        use_cache: bool,
+    ) {
+        log::info!("loading database from {url}");
+        std::thread::spawn(move || {
+            let cache_path = config.state_dir().join(DB_CACHE_FILENAME);
+            match req_fn(&url) {
+                Ok(body) => {
+                    if Self::finish_loading(&status, &db, Ok(&body)) && use_cache {
+                        let _ = std::fs::create_dir_all(config.state_dir());
+                        if let Err(e) = std::fs::write(&cache_path, &body) {
+                            log::error!("failed to write database cache {cache_path:?}: {e}");
+                        }
+                    }
+                }
+                Err(e) => {
+                    let Some((body, cached_at_unix)) = use_cache
+                        .then(|| read_db_cache(&cache_path, config.cache_ttl_secs))
+                        .flatten()
+                    else {
+                        Self::finish_loading(&status, &db, Err(e));
+                        return;
+                    };
+                    log::warn!("failed to load database ({e}), using cache from {cache_path:?}");
+                    if Self::finish_loading(&status, &db, Ok(&body)) {
+                        *status.write().unwrap() = Status::LoadedFromCache {
+                            did_filter: false,
+                            cached_at_unix,
+                        };
+                    }
+                }
+            }
+        });
+    }
+
+    fn load_database_from_path(
+        status: Arc<RwLock<Status>>,
+        db: Arc<RwLock<Database>>,
+        path: PathBuf,
+    ) {
+        log::info!("loading database from {path:?}");
+        std::thread::spawn(move || {
+            match std::fs::read(&path) {
+                Ok(body) => Self::finish_loading(&status, &db, Ok(&body)),
+                Err(e) => Self::finish_loading(&status, &db, Err(e.to_string())),
+            };
+        });
+    }
+
+    /// Parses the database and replaces the loaded one. Returns whether it was loaded.
+    fn finish_loading(
+        status: &RwLock<Status>,
+        db: &RwLock<Database>,
+        body: Result<&[u8], String>,
+    ) -> bool {
+        match body {
             Ok(body) => {
-                *db.write().unwrap() = match serde_json::from_slice(&body) {
+                let mut new_db: Database = match serde_json::from_slice(body) {
                     Ok(entries) => entries,
                     Err(e) => {
                         log::error!("failed to parse database: {e}");
                         *status.write().unwrap() = Status::Error(e.to_string());
-                        return;
+                        return false;
+                    }
+                };
+                {
+                    // keep imported clickpacks across refreshes
+                    let mut old_db = db.write().unwrap();
+                    for (name, entry) in old_db.entries.drain(..) {
+                        if entry.is_local && !new_db.entries.contains_key(&name) {
+                            new_db.entries.insert(name, entry);
+                        }
+                    }
+                    *old_db = new_db;
+                }
+                log::info!("loaded {} entries", db.read().unwrap().entries.len());
+                *status.write().unwrap() = Status::Loaded { did_filter: false };
+                true
+            }
+            Err(e) => {
+                log::error!("failed to load database: {e}");
+                *status.write().unwrap() = Status::Error(e);
+                false
+            }
+        }
+    }
+
+    /// Loads the database from a local `db.json` instead of fetching it over the
+    /// network. The configured database URL and the `req_fn` passed to
+    /// [`ClickpackDb::show`] are not used for loading the database after this
+    /// (refreshing reads the file again), but are still used for downloads.
+    pub fn load_from_path(&mut self, path: PathBuf) {
+        *self.status.write().unwrap() = Status::Loading;
+        Self::load_database_from_path(self.status.clone(), self.db.clone(), path.clone());
+        self.local_db_path = Some(path);
+    }
+
+    fn update_filtered_entries(&mut self) {
+        if self.state.tags != self.tags {
+            self.state.tags = self.tags.clone();
+            self.save_state();
+        }
+        self.filtered_entries = self.db.read().unwrap().entries.clone();
+        if !self.blocked_entries.is_empty() {
+            self.filtered_entries
+                .retain(|k, _| !self.blocked_entries.contains(k));
+        }
+        if let Some(ref custom_filter) = self.custom_filter {
+            self.filtered_entries.retain(|k, v| custom_filter(k, v));
+        }
+        for (name, overrides) in &self.local_overrides {
+            if let Some(entry) = self.filtered_entries.get_mut(name) {
+                overrides.apply(entry);
+            }
+        }
+
+        // handle tags
+        if self.tags.has_any() {
+            self.filtered_entries.retain(|k, v| {
+                if self.tags.noise && !v.has_noise {
+                    return false;
+                }
+                if self.tags.softclicks && !v.has_softclicks {
+                    return false;
+                }
+                if self.tags.hardclicks && !v.has_hardclicks {
+                    return false;
+                }
+                if self.tags.downloaded
+                    && !matches!(v.dwn_status, DownloadStatus::Downloaded { .. })
+                {
+                    return false;
+                }
+                if self.tags.favorites && !self.favorites.contains(k) {
+                    return false;
+                }
+                if self.tags.featured && !v.featured {
+                    return false;
+                }
+                if self.tags.cc0_only && v.license.as_deref() != Some("CC0") {
+                    return false;
+                }
+                if !self.tags.official.iter().all(|tag| v.tags.contains(tag)) {
+                    return false;
+                }
+                if !self
+                    .tags
+                    .compatible_with
+                    .iter()
+                    .all(|system| v.compatible_with.contains(system))
+                {
+                    return false;
+                }
+                if self
+                    .tags
+                    .size_min_bytes
+                    .is_some_and(|min| v.uncompressed_size < min)
+                    || self
+                        .tags
+                        .size_max_bytes
+                        .is_some_and(|max| v.uncompressed_size > max)
+                {
+                    return false;
+                }
+                if self.tags.min_downloads.is_some_and(|min| v.downloads < min) {
+                    return false;
+                }
+                if let Some(letter) = self.tags.name_starts_with {
+                    let first = k.chars().next().and_then(|c| c.to_uppercase().next());
+                    if first != letter.to_uppercase().next() {
+                        return false;
+                    }
+                }
+                if !self.tags.custom.is_empty() {
+                    let entry_tags = self.custom_tags.get(k);
+                    if !self
+                        .tags
+                        .custom
+                        .iter()
+                        .all(|tag| entry_tags.is_some_and(|tags| tags.contains(tag)))
+                    {
+                        return false;
+                    }
+                }
+                true
+            });
+        }
+
+        // sort by the selected column
+        if let Some((column, ascending)) = self.sort_order.or(self.default_order.sort_order()) {
+            self.filtered_entries.sort_by(|ka, a, kb, b| {
+                let ord = match column {
+                    SortColumn::Name => ka.to_lowercase().cmp(&kb.to_lowercase()),
+                    SortColumn::Size => a.size.cmp(&b.size),
+                    SortColumn::UncompressedSize => a.uncompressed_size.cmp(&b.uncompressed_size),
+                    SortColumn::Downloads => a.downloads.cmp(&b.downloads),
+                    SortColumn::Noise => a.has_noise.cmp(&b.has_noise),
+                };
+                if ascending {
+                    ord
+                } else {
+                    ord.reverse()
+                }
+            });
+        }
+
+        // search tokens like `noise:yes` are hard filters
+        let (search_tokens, query) = parse_search_tokens(&self.search_query);
+        if !search_tokens.is_empty() {
+            self.filtered_entries
+                .retain(|_, v| search_tokens.iter().all(|token| token.matches(v)));
+        }
+
+        // fuzzy sort with search query (stable, so ties keep the column order)
+        if !query.is_empty() {
+            let matcher = fuzzy_matcher::skim::SkimMatcherV2::default();
+            let matcher = if self.case_sensitive {
+                matcher.respect_case()
+            } else {
+                matcher.ignore_case()
+            };
+            self.filtered_entries.sort_by_cached_key(|k, v| {
+                // also match authors, so clicking an author brings up all of their packs
+                let author_score = v
+                    .author
+                    .as_ref()
+                    .and_then(|author| matcher.fuzzy_match(author, &query));
+                let display_name_score = self
+                    .display_name_overrides
+                    .get(k)
+                    .and_then(|display| matcher.fuzzy_match(display, &query));
+                std::cmp::Reverse(
+                    matcher
+                        .fuzzy_match(k, &query)
+                        .max(author_score)
+                        .max(display_name_score)
+                        .unwrap_or(0),
+                )
+            });
+        }
+
+        // featured entries go first (stable, so they keep their order)
+        self.filtered_entries
+            .sort_by(|_, a, _, b| b.featured.cmp(&a.featured));
+
+        self.update_visible_entries();
+    }
+
+    /// Filters the table once the search query stopped changing for
+    /// [`ClickpackDbConfig::search_debounce_ms`].
+    fn update_debounced_search(&mut self, ctx: &egui::Context) {
+        let Some(changed_at) = self.last_search_change else {
+            return;
+        };
+        let debounce = std::time::Duration::from_millis(self.config.search_debounce_ms);
+        let elapsed = changed_at.elapsed();
+        if elapsed >= debounce {
+            self.last_search_change = None;
+            self.update_filtered_entries();
+        } else {
+            ctx.request_repaint_after(debounce - elapsed);
+        }
+    }
+
+    /// Rebuilds `visible_entries` from the current page of `filtered_entries`.
+    fn update_visible_entries(&mut self) {
+        let page_size = self.config.page_size.max(1);
+        let nr_pages = self.filtered_entries.len().div_ceil(page_size).max(1);
+        self.page = self.page.min(nr_pages - 1);
+        self.visible_entries = self
+            .filtered_entries
+            .iter()
+            .skip(self.page * page_size)
+            .take(page_size)
+            .map(|(name, entry)| (name.clone(), entry.clone()))
+            .collect();
+        self.visible_generation = self.visible_generation.wrapping_add(1);
+    }
+
+    /// Sets a request function that reports download progress. When set, it is
+    /// used instead of the regular `req_fn` for clickpack downloads, which lets
+    /// the table show a progress bar.
+    pub fn set_streaming_req_fn(&mut self, streaming_req_fn: &'static StreamingRequestFn) {
+        self.streaming_req_fn = Some(streaming_req_fn);
+    }
+
+    fn save_state(&self) {
+        if self.state_from_embedder {
+            return;
+        }
+        write_state_file(&self.config.state_dir(), STATE_FILENAME, &self.state);
+    }
+
+    /// Writes the favorites list to `favorites.json`. Does nothing if the state
+    /// was set with [`ClickpackDb::deserialize_state`].
+    pub fn save_favorites(&self) {
+        if self.state_from_embedder {
+            return;
+        }
+        write_state_file(
+            &self.config.state_dir(),
+            FAVORITES_FILENAME,
+            &self.favorites,
+        );
+    }
+
+    /// Loads the favorites list from `favorites.json`. This is called
+    /// automatically after the database is loaded.
+    pub fn load_favorites(&mut self) {
+        self.favorites = read_state_file(&self.config.state_dir(), FAVORITES_FILENAME);
+    }
+
+    /// Adds or removes a clickpack from the favorites and saves the list.
+    pub fn toggle_favorite(&mut self, name: &str) {
+        if !self.favorites.remove(name) {
+            self.favorites.insert(name.to_string());
+        }
+        self.save_favorites();
+        if self.tags.favorites {
+            self.update_filtered_entries();
+        }
+    }
+
+    /// Writes the user-defined tags to `custom_tags.json`. Does nothing if the
+    /// state was set with [`ClickpackDb::deserialize_state`].
+    pub fn save_custom_tags(&self) {
+        if self.state_from_embedder {
+            return;
+        }
+        write_state_file(
+            &self.config.state_dir(),
+            CUSTOM_TAGS_FILENAME,
+            &self.custom_tags,
+        );
+    }
+
+    /// Loads the user-defined tags from `custom_tags.json`. This is called
+    /// automatically after the database is loaded.
+    pub fn load_custom_tags(&mut self) {
+        self.custom_tags = read_state_file(&self.config.state_dir(), CUSTOM_TAGS_FILENAME);
+    }
+
+    /// Writes the user changes to entries to `local_overrides.json`. Does nothing
+    /// if the state was set with [`ClickpackDb::deserialize_state`].
+    pub fn save_local_overrides(&self) {
+        if self.state_from_embedder {
+            return;
+        }
+        write_state_file(
+            &self.config.state_dir(),
+            LOCAL_OVERRIDES_FILENAME,
+            &self.local_overrides,
+        );
+    }
+
+    /// Loads the user changes to entries from `local_overrides.json`. This is
+    /// called automatically after the database is loaded.
+    pub fn load_local_overrides(&mut self) {
+        self.local_overrides = read_state_file(&self.config.state_dir(), LOCAL_OVERRIDES_FILENAME);
+    }
+
+    /// Overrides whether a clickpack is considered to have a noise file, or
+    /// goes back to the database value if `has_noise` is [`None`].
+    pub fn set_has_noise_override(&mut self, name: &str, has_noise: Option<bool>) {
+        let overrides = self.local_overrides.entry(name.to_string()).or_default();
+        overrides.has_noise = has_noise;
+        if overrides.has_noise.is_none() {
+            self.local_overrides.remove(name);
+        }
+        self.save_local_overrides();
+        self.update_filtered_entries();
+    }
+
+    /// Adds a user-defined tag to a clickpack and saves the tags.
+    pub fn add_custom_tag(&mut self, name: &str, tag: &str) {
+        let tag = tag.trim();
+        if tag.is_empty() {
+            return;
+        }
+        let tags = self.custom_tags.entry(name.to_string()).or_default();
+        if !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+            self.save_custom_tags();
+        }
+    }
+
+    /// Removes a user-defined tag from a clickpack and saves the tags.
+    pub fn remove_custom_tag(&mut self, name: &str, tag: &str) {
+        let Some(tags) = self.custom_tags.get_mut(name) else {
+            return;
+        };
+        tags.retain(|t| t != tag);
+        if tags.is_empty() {
+            self.custom_tags.remove(name);
+        }
+        self.save_custom_tags();
+        if !self.tags.custom.is_empty() {
+            self.update_filtered_entries();
+        }
+    }
+
+    /// All user-defined tags, sorted and de-duplicated.
+    fn all_custom_tags(&self) -> Vec<String> {
+        let tags: BTreeSet<&String> = self.custom_tags.values().flatten().collect();
+        tags.into_iter().cloned().collect()
+    }
+
+    /// All official tags in the database, sorted and de-duplicated.
+    fn all_official_tags(&self) -> Vec<String> {
+        let db = self.db.read().unwrap();
+        let tags: BTreeSet<&String> = db.entries.values().flat_map(|e| &e.tags).collect();
+        tags.into_iter().cloned().collect()
+    }
+
+    /// All bots that entries in the database are compatible with, sorted and de-duplicated.
+    fn all_compatible_systems(&self) -> Vec<String> {
+        let db = self.db.read().unwrap();
+        let systems: BTreeSet<&String> = db
+            .entries
+            .values()
+            .flat_map(|e| &e.compatible_with)
+            .collect();
+        systems.into_iter().cloned().collect()
+    }
+
+    /// Restores download statuses from the state sidecar written in previous sessions.
+    ///
+    /// Clickpacks whose directory no longer exists are forgotten. This is called
+    /// automatically after the database is loaded.
+    pub fn restore_download_state(&mut self) {
+        self.state = read_state_file(&self.config.state_dir(), STATE_FILENAME);
+        self.tags = self.state.tags.clone();
+        self.apply_download_state();
+    }
+
+    /// Marks the clickpacks in `state.downloaded` as downloaded.
+    fn apply_download_state(&mut self) {
+        let mut db = self.db.write().unwrap();
+        let mut nr_restored = 0;
+        self.state.downloaded.retain(|name, path| {
+            if !path.try_exists().unwrap_or(false) {
+                return false;
+            }
+            if let Some(entry) = db.entries.get_mut(name) {
+                entry.dwn_status = DownloadStatus::Downloaded {
+                    path: path.clone(),
+                    do_select: false,
+                    is_extracted: !path.is_file(),
+                };
+                nr_restored += 1;
+            }
+            true
+        });
+        let downloaded = &self.state.downloaded;
+        self.state
+            .downloaded_versions
+            .retain(|name, _| downloaded.contains_key(name));
+        log::info!("restored {nr_restored} downloaded clickpacks");
+    }
+
+    /// Marks clickpacks that already exist in the immediate subdirectories of `path`
+    /// as downloaded, e.g. after reinstalling or moving the clickpack folder.
+    ///
+    /// Directory names are matched case-insensitively, treating underscores as
+    /// spaces. Clickpacks that are already downloaded or downloading are left
+    /// alone. Returns the names of the matched clickpacks.
+    pub fn scan_local_directory(&mut self, path: PathBuf) -> Vec<String> {
+        let normalize = |name: &str| name.trim().replace('_', " ").to_lowercase();
+        let Ok(dir) = std::fs::read_dir(&path) else {
+            log::error!("failed to read directory {path:?}");
+            return vec![];
+        };
+
+        let mut db = self.db.write().unwrap();
+        let names: HashMap<String, String> = db
+            .entries
+            .keys()
+            .map(|name| (normalize(name), name.clone()))
+            .collect();
+        let mut matched = vec![];
+        for dir_entry in dir.flatten() {
+            let dir_path = dir_entry.path();
+            if !dir_path.is_dir() {
+                continue;
+            }
+            let Some(name) = dir_path
+                .file_name()
+                .and_then(|name| names.get(&normalize(&name.to_string_lossy())))
+            else {
+                continue;
+            };
+            // don't touch clickpacks that are downloaded elsewhere or downloading
+            let Some(entry) = db
+                .entries
+                .get_mut(name)
+                .filter(|e| matches!(e.dwn_status, DownloadStatus::NotDownloaded))
+            else {
+                continue;
+            };
+            let status = DownloadStatus::Downloaded {
+                path: dir_path.clone(),
+                do_select: false,
+                is_extracted: true,
+            };
+            entry.dwn_status = status.clone();
+            if let Some(entry) = self.filtered_entries.get_mut(name) {
+                entry.dwn_status = status;
+            }
+            self.state.downloaded.insert(name.clone(), dir_path);
+            matched.push(name.clone());
+        }
+        drop(db);
+        if !matched.is_empty() {
+            self.update_visible_entries();
+        }
+
+        log::info!("found {} downloaded clickpacks in {path:?}", matched.len());
+        if !matched.is_empty() {
+            self.save_state();
+        }
+        matched
+    }
+
+    /// Serializes the currently visible (filtered and sorted) entries as RFC 4180 CSV.
+    pub fn export_database_csv(&self) -> String {
+        let mut csv =
+            String::from("name,size,uncompressed_size,downloads,has_noise,author,url\r\n");
+        for (name, entry) in &self.filtered_entries {
+            csv += &format!(
+                "{},{},{},{},{},{},{}\r\n",
+                csv_field(name),
+                entry.size,
+                entry.uncompressed_size,
+                entry.downloads,
+                entry.has_noise,
+                csv_field(entry.author.as_deref().unwrap_or_default()),
+                csv_field(&entry.url),
+            );
+        }
+        csv
+    }
+
+    /// Serializes the whole database as pretty-printed JSON.
+    pub fn export_database_json(&self) -> String {
+        serde_json::to_string_pretty(&*self.db.read().unwrap()).unwrap_or_else(|e| {
+            log::error!("failed to serialize database: {e}");
+            String::new()
+        })
+    }
+
+    /// Compares the loaded database against the one seen in the previous session.
+    fn check_database_update(&mut self) {
+        let updated_at_unix = self.db.read().unwrap().updated_at_unix;
+        let last_seen = self.state.last_seen_updated_at;
+        if last_seen != 0 && updated_at_unix > last_seen {
+            log::info!("database was updated since the last session");
+            self.show_update_banner = true;
+        }
+
+        let seen: HashSet<String> = self
+            .db
+            .read()
+            .unwrap()
+            .entries
+            .iter()
+            .filter(|(_, e)| !e.is_local)
+            .map(|(k, _)| k.clone())
+            .collect();
+        if seen != self.state.previously_seen_entries {
+            // everything would be new on the first run
+            if !self.state.previously_seen_entries.is_empty() {
+                self.new_entries = seen
+                    .difference(&self.state.previously_seen_entries)
+                    .cloned()
+                    .collect();
+            }
+            self.state.previously_seen_entries = seen;
+            self.save_state();
+        }
+
+        if updated_at_unix != last_seen || self.state.entry_sizes.is_empty() {
+            let db = self.db.read().unwrap();
+            let entry_sizes: HashMap<String, usize> = db
+                .entries
+                .iter()
+                .filter(|(_, e)| !e.is_local)
+                .map(|(k, e)| (k.clone(), e.size))
+                .collect();
+            if !self.state.entry_sizes.is_empty() {
+                self.whats_new = entry_sizes
+                    .iter()
+                    .filter(|&(k, size)| self.state.entry_sizes.get(k) != Some(size))
+                    .map(|(k, _)| k.clone())
+                    .collect();
+                self.whats_new.sort();
+            }
+            drop(db);
+            self.state.entry_sizes = entry_sizes;
+            self.state.last_seen_updated_at = updated_at_unix;
+            self.save_state();
+        }
+    }
+
+    fn whats_new_panel(&mut self, ui: &mut egui::Ui) {
+        if self.whats_new.is_empty() {
+            return;
+        }
+        let mut jump_to = None;
+        egui::CollapsingHeader::new(format!("What's new ({})", self.whats_new.len()))
+            .id_salt("clickpackdb_whats_new")
+            .show(ui, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    for name in &self.whats_new {
+                        if ui
+                            .link(self.display_name(name))
+                            .on_hover_text("New or updated since your last session")
+                            .clicked()
+                        {
+                            jump_to = Some(name.clone());
+                        }
+                    }
+                });
+            });
+        if let Some(name) = jump_to {
+            self.jump_to_entry(name);
+        }
+    }
+
+    /// Scrolls the table to an entry and briefly highlights it. Filters are
+    /// cleared if they hide the entry.
+    fn jump_to_entry(&mut self, name: String) {
+        if !self.filtered_entries.contains_key(&name) {
+            self.search_query.clear();
+            self.tags = Tags::default();
+            self.update_filtered_entries();
+        }
+        self.highlighted_entry = Some((name.clone(), std::time::Instant::now()));
+        self.scroll_to_entry = Some(name);
+    }
+
+    /// Paints the fading highlight behind a cell of the highlighted entry.
+    fn paint_highlight(&self, ui: &egui::Ui, name: &str) {
+        let Some((ref highlighted, at)) = self.highlighted_entry else {
+            return;
+        };
+        if highlighted != name {
+            return;
+        }
+        let t = at.elapsed().as_secs_f32() / HIGHLIGHT_DURATION.as_secs_f32();
+        if t >= 1.0 {
+            return;
+        }
+        let color = ui.visuals().selection.bg_fill.gamma_multiply(1.0 - t);
+        ui.painter().rect_filled(ui.max_rect(), 0.0, color);
+        ui.ctx().request_repaint();
+    }
+
+    fn update_banner(&mut self, ui: &mut egui::Ui, req_fn: &'static RequestFn) {
+        if !self.show_update_banner {
+            return;
+        }
+        let updated_at_unix = self.db.read().unwrap().updated_at_unix;
+        ui.horizontal(|ui| {
+            ui.style_mut().spacing.item_spacing.x = 5.0;
+            ui.colored_label(
+                ui.visuals().hyperlink_color,
+                format!(
+                    "Database updated {} — refresh to see new packs",
+                    format_db_age(updated_at_unix)
+                ),
+            );
+            self.refresh_button(ui, req_fn);
+            if ui.small_button("✕").on_hover_text("Dismiss").clicked() {
+                self.show_update_banner = false;
+            }
+        });
+    }
+
+    /// Whether a downloaded clickpack has a different version in the database
+    /// than the one that was downloaded.
+    fn has_update(&self, name: &str, entry: &Entry) -> bool {
+        matches!(entry.dwn_status, DownloadStatus::Downloaded { .. })
+            && entry.version.as_ref().is_some_and(|version| {
+                self.state
+                    .downloaded_versions
+                    .get(name)
+                    .is_some_and(|downloaded| downloaded != version)
+            })
+    }
+
+    /// Sum of the uncompressed sizes of all downloaded clickpacks, in bytes.
+    pub fn total_downloaded_size(&self) -> usize {
+        self.db
+            .read()
+            .unwrap()
+            .entries
+            .values()
+            .filter(|e| matches!(e.dwn_status, DownloadStatus::Downloaded { .. }))
+            .map(|e| e.uncompressed_size)
+            .sum()
+    }
+
+    #[cfg(feature = "live")]
+    pub fn mark_downloaded(&mut self, name: &str, path: PathBuf, downloaded: bool) {
+        let update_status = |status: &mut DownloadStatus| {
+            if downloaded {
+                *status = DownloadStatus::Downloaded {
+                    path: path.clone(),
+                    do_select: false,
+                    is_extracted: true,
+                };
+            } else {
+                *status = DownloadStatus::NotDownloaded;
+            }
+        };
+
+        if let Some(entry) = self.db.write().unwrap().entries.get_mut(name) {
+            update_status(&mut entry.dwn_status);
+        }
+        if let Some(entry) = self.filtered_entries.get_mut(name) {
+            update_status(&mut entry.dwn_status);
+            self.update_visible_entries();
+        }
+        if downloaded {
+            self.state.downloaded.insert(name.to_string(), path);
+        } else {
+            self.state.downloaded.remove(name);
+        }
+        self.save_state();
+    }
+
+    /// Batch version of [`ClickpackDb::mark_downloaded`], e.g. after scanning
+    /// `.zcb/clickpacks` on startup. Maps names to the directory they were
+    /// downloaded to, or [`None`] if they aren't downloaded. The database is
+    /// locked and the table is filtered only once.
+    #[cfg(feature = "live")]
+    pub fn batch_update_download_status(&mut self, statuses: HashMap<String, Option<PathBuf>>) {
+        let mut db = self.db.write().unwrap();
+        for (name, path) in statuses {
+            let status = match path {
+                Some(ref path) => DownloadStatus::Downloaded {
+                    path: path.clone(),
+                    do_select: false,
+                    is_extracted: true,
+                },
+                None => DownloadStatus::NotDownloaded,
+            };
+            if let Some(entry) = db.entries.get_mut(&name) {
+                entry.dwn_status = status;
+            }
+            match path {
+                Some(path) => self.state.downloaded.insert(name, path),
+                None => self.state.downloaded.remove(&name),
+            };
+        }
+        drop(db);
+        self.save_state();
+        self.update_filtered_entries();
+    }
+
+    fn update_queue(&self) {
+        let items = self
+            .db
+            .read()
+            .unwrap()
+            .entries
+            .iter()
+            .filter(|(_, e)| {
+                e.dwn_status.is_in_progress() || matches!(e.dwn_status, DownloadStatus::Error(_))
+            })
+            .map(|(k, v)| (k.clone(), v.dwn_status.clone()))
+            .collect();
+        self.queue.write().unwrap().items = items;
+    }
+
+    /// Finds entries that share a download URL (e.g. renamed entries) and marks
+    /// them as downloaded if one of them already is.
+    fn find_shared_urls(&mut self) {
+        let mut urls: HashMap<String, Vec<String>> = HashMap::new();
+        for (name, entry) in &self.db.read().unwrap().entries {
+            // imported ZIP files have no URL or one of their own
+            if entry.url.is_empty() || entry.is_local {
+                continue;
+            }
+            urls.entry(entry.url.clone())
+                .or_default()
+                .push(name.clone());
+        }
+        urls.retain(|_, names| names.len() > 1);
+        if !urls.is_empty() {
+            log::info!(
+                "found {} download URLs shared by multiple entries",
+                urls.len()
+            );
+        }
+        self.shared_urls = urls;
+        if self.link_shared_urls() {
+            self.save_state();
+        }
+    }
+
+    /// Marks entries as downloaded if another entry with the same URL is.
+    /// Returns whether any entry was marked.
+    fn link_shared_urls(&mut self) -> bool {
+        let mut db = self.db.write().unwrap();
+        let mut linked = false;
+        for names in self.shared_urls.values() {
+            let Some(path) = names.iter().find_map(|name| {
+                db.entries
+                    .get(name)
+                    .and_then(|e| e.downloaded_path().map(Path::to_path_buf))
+            }) else {
+                continue;
+            };
+            for name in names {
+                let Some(entry) = db.entries.get_mut(name) else {
+                    continue;
+                };
+                if !matches!(entry.dwn_status, DownloadStatus::NotDownloaded) {
+                    continue;
+                }
+                entry.dwn_status = DownloadStatus::Downloaded {
+                    path: path.clone(),
+                    do_select: false,
+                    is_extracted: !path.is_file(),
+                };
+                if let Some(filtered) = self.filtered_entries.get_mut(name) {
+                    filtered.dwn_status = entry.dwn_status.clone();
+                }
+                self.state.downloaded.insert(name.clone(), path.clone());
+                linked = true;
+            }
+        }
+        drop(db);
+        if linked {
+            self.update_visible_entries();
+        }
+        linked
+    }
+
+    fn update_pending_update(&mut self) {
+        let mut is_empty = true;
+        let mut state_changed = false;
+        let mut events = vec![];
+        for (k, v) in self.pending_update.read().unwrap().iter() {
+            is_empty = false;
+            if let DownloadStatus::Error(ref e) = v.dwn_status {
+                let already_failed = self
+                    .db
+                    .read()
+                    .unwrap()
+                    .entries
+                    .get(k)
+                    .is_some_and(|entry| matches!(entry.dwn_status, DownloadStatus::Error(_)));
+                if !already_failed {
+                    events.push(ClickpackDbEvent::DownloadFailed(k.clone(), e.clone()));
+                }
+            }
+            let is_reverted = self.reverted_updates.lock().unwrap().remove(k);
+            match v.dwn_status {
+                // the previous version is still downloaded, nothing completed
+                DownloadStatus::Downloaded { .. } if is_reverted => {}
+                DownloadStatus::Downloaded { ref path, .. } => {
+                    // a queued or retrying status can be replaced before it's shown
+                    let was_downloading = self
+                        .db
+                        .read()
+                        .unwrap()
+                        .entries
+                        .get(k)
+                        .is_some_and(|e| e.dwn_status.is_in_progress());
+                    if was_downloading {
+                        if let Some(ref on_download_complete) = self.on_download_complete {
+                            on_download_complete(k, path);
+                        }
+                        events.push(ClickpackDbEvent::DownloadComplete(k.clone(), path.clone()));
+                    }
+                    self.state.downloaded.insert(k.clone(), path.clone());
+                    match v.version {
+                        Some(ref version) => {
+                            self.state
+                                .downloaded_versions
+                                .insert(k.clone(), version.clone());
+                        }
+                        None => {
+                            self.state.downloaded_versions.remove(k);
+                        }
+                    }
+                    state_changed = true;
+                }
+                _ => {}
+            }
+            self.db
+                .write()
+                .unwrap()
+                .entries
+                .insert(k.clone(), v.clone());
+            if self.filtered_entries.contains_key(k) {
+                let mut v = v.clone();
+                if let Some(overrides) = self.local_overrides.get(k) {
+                    overrides.apply(&mut v);
+                }
+                self.filtered_entries.insert(k.clone(), v);
+            }
+        }
+        if !is_empty {
+            self.pending_update.write().unwrap().clear();
+            self.update_visible_entries();
+        }
+        for event in events {
+            self.emit(event);
+        }
+        if state_changed {
+            self.link_shared_urls();
+            self.save_state();
+        }
+
+        // copy download progress into the entries
+        if !self.download_progress.is_empty() {
+            let mut db = self.db.write().unwrap();
+            self.download_progress.retain(|name, progress| {
+                let Some(entry) = db.entries.get_mut(name) else {
+                    return false;
+                };
+                if !entry.dwn_status.is_in_progress() {
+                    return false;
+                }
+                let progress = *progress.lock().unwrap();
+                let update_progress = |status: &mut DownloadStatus| {
+                    if let DownloadStatus::Downloading {
+                        received, total, ..
+                    } = status
+                    {
+                        (*received, *total) = progress;
+                    }
+                };
+                update_progress(&mut entry.dwn_status);
+                if let Some(entry) = self.filtered_entries.get_mut(name) {
+                    update_progress(&mut entry.dwn_status);
+                }
+                true
+            });
+            drop(db);
+            self.update_visible_entries();
+        }
+        for path in self.pending_clickpack_delete.drain(..) {
+            let result = if path.is_file() {
+                std::fs::remove_file(&path)
+            } else {
+                std::fs::remove_dir_all(&path)
+            };
+            if let Err(e) = result {
+                log::error!("failed to delete clickpack directory {path:?}: {e}");
+            }
+        }
+    }
+
+    pub fn show(
+        &mut self,
+        ui: &mut egui::Ui,
+        req_fn: &'static RequestFn,
+        #[cfg(not(feature = "live"))] pick_folder: &'static PickFolderFn,
+    ) {
+        let mut status = self.status.read().unwrap().clone();
+        match status {
+            Status::NotLoaded => {
+                self.start_loading(req_fn);
+                status = Status::Loading;
+            }
+            Status::Loading => {
+                ui.horizontal(|ui| {
+                    ui.add(egui::Spinner::new());
+                    ui.label("Loading database…");
+                });
+            }
+            Status::Error(ref e) => {
+                self.is_refreshing = false;
+                ui.colored_label(
+                    self.theme.error_color,
+                    format!("Error loading database: {e}"),
+                );
+            }
+            Status::Loaded { did_filter } | Status::LoadedFromCache { did_filter, .. } => {
+                if !did_filter {
+                    // the refresh cooldown only starts once a refresh succeeds
+                    if self.is_refreshing && matches!(status, Status::Loaded { .. }) {
+                        self.last_refresh_time = Some(std::time::Instant::now());
+                    }
+                    self.is_refreshing = false;
+                    if self.state_from_embedder {
+                        self.apply_download_state();
+                    } else {
+                        self.restore_download_state();
+                        self.load_favorites();
+                        self.load_custom_tags();
+                        self.load_local_overrides();
+                    }
+                    self.find_shared_urls();
+                    self.check_database_update();
+                    self.update_filtered_entries();
+                    self.emit(ClickpackDbEvent::DatabaseLoaded);
+                    #[cfg(feature = "live")]
+                    {
+                        self.has_refreshed = true;
+                    }
+                    if let Status::Loaded { did_filter }
+                    | Status::LoadedFromCache { did_filter, .. } =
+                        &mut *self.status.write().unwrap()
+                    {
+                        *did_filter = true;
+                    }
+                }
+                if let Status::LoadedFromCache { cached_at_unix, .. } = status {
+                    ui.horizontal(|ui| {
+                        ui.style_mut().spacing.item_spacing.x = 5.0;
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            format!(
+                                "Offline — showing cached data from {}",
+                                format_db_age(cached_at_unix)
+                            ),
+                        );
+                        self.refresh_button(ui, req_fn);
+                    });
+                }
+                self.handle_dropped_files(ui.ctx());
+                if ui.ctx().input(|i| !i.raw.hovered_files.is_empty()) {
+                    ui.label("Drop ZIP files here to import them as clickpacks");
+                }
+            }
+        }
+        self.update_pending_update();
+        self.update_debounced_search(ui.ctx());
+        if let Some(preview) = self.pending_preview_audio.lock().unwrap().take() {
+            self.preview_audio = Some(preview);
+        }
+        self.handle_watch_events();
+        self.update_queue();
+        ui.add_enabled_ui(
+            !matches!(status, Status::NotLoaded | Status::Loading),
+            |ui| {
+                #[cfg(not(feature = "live"))]
+                self.show_table(ui, req_fn, pick_folder);
+                #[cfg(feature = "live")]
+                self.show_table(ui, req_fn);
+            },
+        );
+    }
+
+    /// Starts watching the download directory for clickpacks that are extracted
+    /// or deleted outside of the widget. New directories are matched like in
+    /// [`ClickpackDb::scan_local_directory`] and removed ones are marked as not
+    /// downloaded. Stops watching when the widget is reset or dropped.
+    pub fn watch_download_directory(&mut self) {
+        use notify::Watcher;
+        self.watcher = None;
+        let dir = self.download_dir();
+        log::info!("watching download directory {dir:?}");
+        let _ =
+            std::fs::create_dir_all(&dir).map_err(|e| log::error!("create_dir_all failed: {e}"));
+        let watch_events = self.watch_events.clone();
+        let watched_dir = dir.clone();
+        let watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| match event {
+                Ok(event) => watch_events
+                    .lock()
+                    .unwrap()
+                    .extend(WatchEvent::from_notify(event, &watched_dir)),
+                Err(e) => log::error!("download directory watcher failed: {e}"),
+            });
+        match watcher.and_then(|mut watcher| {
+            watcher.watch(&dir, notify::RecursiveMode::NonRecursive)?;
+            Ok(watcher)
+        }) {
+            Ok(watcher) => self.watcher = Some(watcher),
+            Err(e) => log::error!("failed to watch download directory {dir:?}: {e}"),
+        }
+    }
+
+    fn handle_watch_events(&mut self) {
+        let events = std::mem::take(&mut *self.watch_events.lock().unwrap());
+        for event in events {
+            match event {
+                WatchEvent::Created(path) => {
+                    if let Some(parent) = path.parent() {
+                        self.scan_local_directory(parent.to_path_buf());
+                    }
+                }
+                WatchEvent::Removed(path) => {
+                    let removed: Vec<String> = self
+                        .db
+                        .read()
+                        .unwrap()
+                        .entries
+                        .iter()
+                        .filter(|(_, e)| e.downloaded_path() == Some(path.as_path()))
+                        .map(|(k, _)| k.clone())
+                        .collect();
+                    if removed.is_empty() {
+                        continue;
+                    }
+                    for name in removed {
+                        log::info!("clickpack directory of \"{name}\" was removed");
+                        self.state.downloaded.remove(&name);
+                        self.set_download_status(&name, DownloadStatus::NotDownloaded);
+                    }
+                    self.save_state();
+                }
+            }
+        }
+    }
+
+    /// Resets the widget back to [`Status::NotLoaded`], as if it was just created.
+    ///
+    /// The database, search query, tags, selection and all pending work are
+    /// cleared and in-flight downloads are cancelled. The configuration and
+    /// callbacks set by the embedder are kept, so the database is loaded again
+    /// on the next [`ClickpackDb::show`].
+    pub fn reset(&mut self) {
+        log::info!("resetting clickpackdb");
+        self.watcher = None;
+        for entry in self.db.read().unwrap().entries.values() {
+            if let Some(cancel) = entry.dwn_status.cancel_token() {
+                cancel.cancel();
+            }
+        }
+        // the saved filters would be restored on the next load otherwise
+        self.state.tags = Tags::default();
+        self.save_state();
+        *self = Self {
+            config: std::mem::take(&mut self.config),
+            preview_callback: self.preview_callback.take(),
+            on_select: self.on_select.take(),
+            on_download_complete: self.on_download_complete.take(),
+            extra_columns: std::mem::take(&mut self.extra_columns),
+            subscriptions: std::mem::take(&mut self.subscriptions),
+            theme: self.theme,
+            queue: self.queue.clone(),
+            blocked_entries: std::mem::take(&mut self.blocked_entries),
+            custom_filter: self.custom_filter.take(),
+            display_name_overrides: std::mem::take(&mut self.display_name_overrides),
+            default_order: self.default_order,
+            view_mode: self.view_mode,
+            popular_threshold: self.popular_threshold,
+            highlight_popular: self.highlight_popular,
+            streaming_req_fn: self.streaming_req_fn,
+            download_slots: self.download_slots.clone(),
+            local_db_path: self.local_db_path.take(),
+            #[cfg(not(feature = "live"))]
+            download_directory: self.download_directory.take(),
+            ..Default::default()
+        };
+    }
+
+    /// Sets the directory that clickpacks are downloaded into when pressing
+    /// "Select", instead of the temp directory. It is created if it doesn't exist.
+    #[cfg(not(feature = "live"))]
+    pub fn set_download_directory(&mut self, path: PathBuf) {
+        self.download_directory = Some(path);
+    }
+
+    /// Directory that clickpacks are downloaded into.
+    fn download_dir(&self) -> PathBuf {
+        #[cfg(not(feature = "live"))]
+        {
+            self.download_directory
+                .clone()
+                .unwrap_or_else(|| self.config.temp_dir())
+        }
+        #[cfg(feature = "live")]
+        {
+            PathBuf::from(".zcb").join("clickpacks")
+        }
+    }
+
+    /// Creates a new directory for a clickpack in [`Self::download_dir`], appending
+    /// underscores to the name until it doesn't collide with an existing one.
+    fn unique_download_path(&self, name: &str) -> PathBuf {
+        let mut new_name = name.to_string();
+        let mut path = self.download_dir().join(&new_name);
+        while path.try_exists().unwrap_or(false) {
+            path.pop();
+            new_name += "_";
+            path.push(&new_name);
+        }
+
+        let _ =
+            std::fs::create_dir_all(&path).map_err(|e| log::error!("create_dir_all failed: {e}"));
+        path
+    }
+
+    fn handle_dropped_files(&mut self, ctx: &egui::Context) {
+        let dropped_files = ctx.input(|i| i.raw.dropped_files.clone());
+        for file in dropped_files {
+            let file_path = file
+                .path
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(&file.name));
+            if !file_path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+            {
+                log::warn!("ignoring dropped file {file_path:?}, not a zip");
+                continue;
+            }
+            let Some(stem) = file_path.file_stem() else {
+                continue;
+            };
+            let body = match (file.bytes, file.path) {
+                (Some(bytes), _) => bytes.to_vec(),
+                (None, Some(path)) => match std::fs::read(&path) {
+                    Ok(body) => body,
+                    Err(e) => {
+                        log::error!("failed to read dropped file {path:?}: {e}");
+                        continue;
+                    }
+                },
+                (None, None) => continue,
+            };
+            self.import_zip(stem.to_string_lossy().into_owned(), body);
+        }
+    }
+
+    /// Extracts a local clickpack ZIP and adds it to the database as a local entry.
+    fn import_zip(&mut self, name: String, body: Vec<u8>) {
+        let mut name = name;
+        while self.db.read().unwrap().entries.contains_key(&name) {
+            name += "_";
+        }
+        let path = self.unique_download_path(&name);
+        log::info!("importing \"{name}\" to {path:?}");
+
+        let cancel = CancelToken::default();
+        let mut entry = Entry {
+            size: body.len(),
+            dwn_status: DownloadStatus::downloading(cancel.clone()),
+            is_local: true,
+            ..Default::default()
+        };
+        self.db
+            .write()
+            .unwrap()
+            .entries
+            .insert(name.clone(), entry.clone());
+        self.update_filtered_entries();
+
+        let pending_update = self.pending_update.clone();
+        std::thread::spawn(move || {
+            if cancel.is_cancelled() {
+                let _ = std::fs::remove_dir(&path);
+                entry.dwn_status = DownloadStatus::NotDownloaded;
+            } else if let Err(e) = zip_extract::extract(Cursor::new(body), &path, true) {
+                log::error!("failed to extract zip to {path:?}: {e}");
+                entry.dwn_status = DownloadStatus::Error(e.to_string());
+            } else {
+                log::info!("successfully imported zip to {path:?}");
+                entry.uncompressed_size = dir_size(&path);
+                entry.dwn_status = DownloadStatus::Downloaded {
+                    path,
+                    do_select: false,
+                    is_extracted: true,
+                };
+            }
+            pending_update.write().unwrap().insert(name, entry);
+        });
+    }
+
+    /// Downloads a clickpack ZIP that isn't in the database and adds it as a
+    /// local entry, like a dropped ZIP file. It is selected once it's extracted.
+    /// If `name` is taken, underscores are appended to it.
+    pub fn import_from_url(&mut self, url: &str, name: &str, req_fn: &'static RequestFn) {
+        let mut name = name.to_string();
+        while self.db.read().unwrap().entries.contains_key(&name) {
+            name += "_";
+        }
+        log::info!("importing \"{name}\" from {url}");
+        let entry = Entry {
+            url: url.to_string(),
+            is_local: true,
+            ..Default::default()
+        };
+        self.db
+            .write()
+            .unwrap()
+            .entries
+            .insert(name.clone(), entry.clone());
+        self.download_and_select(entry, name, req_fn);
+    }
+
+    /// Downloads and extracts the entry called `name` into `path`, without
+    /// going through the UI, e.g. for a `--download foo` command line argument.
+    ///
+    /// Returns an error if there is no such entry, or if it is already
+    /// downloaded or downloading. The download itself runs in a background
+    /// thread, its outcome shows up in the entry's status like any other download.
+    pub fn download_entry_to(
+        &mut self,
+        name: &str,
+        path: PathBuf,
+        req_fn: &'static RequestFn,
+    ) -> Result<(), String> {
+        let Some(entry) = self.entry(name) else {
+            return Err(format!("no clickpack named \"{name}\""));
+        };
+        match entry.dwn_status {
+            DownloadStatus::NotDownloaded | DownloadStatus::Error(_) => {}
+            DownloadStatus::Downloaded { .. } => {
+                return Err(format!("\"{name}\" is already downloaded"));
+            }
+            _ => return Err(format!("\"{name}\" is already downloading")),
+        }
+        self.download_entry(
+            entry,
+            name.to_string(),
+            req_fn,
+            DownloadTarget {
+                path,
+                mode: DownloadMode::Extract { do_select: false },
+                owns_dir: false,
+            },
+            DEFAULT_MAX_RETRIES,
+        );
+        Ok(())
+    }
+
+    /// Deletes the clickpacks downloaded into [`Self::download_dir`] and marks
+    /// them as not downloaded. In-flight downloads are cancelled. Clickpacks
+    /// downloaded into other folders, favorites, tags and other user data are kept.
+    #[cfg(not(feature = "live"))]
+    pub fn clear_download_cache(&mut self) {
+        log::info!("clearing download cache");
+        let download_dir = self.download_dir();
+        let cleared: HashMap<String, PathBuf> = self
+            .state
+            .downloaded
+            .iter()
+            .filter(|(_, path)| path.starts_with(&download_dir))
+            .map(|(name, path)| (name.clone(), path.clone()))
+            .collect();
+        // entries with the same URL share a directory
+        let paths: HashSet<&PathBuf> = cleared.values().collect();
+        for path in paths {
+            let result = if path.is_file() {
+                std::fs::remove_file(path)
+            } else {
+                std::fs::remove_dir_all(path)
+            };
+            if let Err(e) = result {
+                log::error!("failed to delete clickpack {path:?}: {e}");
+            }
+        }
+        for (name, entry) in self.db.write().unwrap().entries.iter_mut() {
+            if let Some(cancel) = entry.dwn_status.cancel_token() {
+                cancel.cancel();
+            }
+            if cleared.contains_key(name) {
+                entry.dwn_status = DownloadStatus::NotDownloaded;
+            }
+        }
+        for name in cleared.keys() {
+            self.state.downloaded.remove(name);
+            self.state.downloaded_versions.remove(name);
+        }
+        log::info!("deleted {} downloaded clickpacks", cleared.len());
+        self.save_state();
+        self.update_filtered_entries();
+    }
+
+    /// Opens the directory of a downloaded clickpack in the file manager.
+    ///
+    /// Returns an error if there is no such entry, if it isn't downloaded, or
+    /// if the file manager couldn't be opened.
+    pub fn open_pack_folder(&self, name: &str) -> Result<(), String> {
+        let Some(entry) = self.entry(name) else {
+            return Err(format!("no clickpack named \"{name}\""));
+        };
+        let Some(mut path) = entry.downloaded_path() else {
+            return Err(format!("\"{name}\" is not downloaded"));
+        };
+        if path.is_file() {
+            // only the ZIP file was saved, open the folder it's in
+            path = path.parent().unwrap_or(path);
+        }
+        open::that(path).map_err(|e| {
+            log::error!("failed to open folder {path:?}: {e}");
+            e.to_string()
+        })
+    }
+
+    /// Fetches the audio preview of an entry in the background, which shows up
+    /// in [`ClickpackDb::preview_audio`].
+    fn fetch_preview_audio(&self, name: &str, url: &str, req_fn: &'static RequestFn) {
+        log::info!("fetching audio preview of \"{name}\" from {url}");
+        let pending_preview_audio = self.pending_preview_audio.clone();
+        let (name, url) = (name.to_string(), url.to_string());
+        std::thread::spawn(move || match req_fn(&url) {
+            Ok(bytes) => *pending_preview_audio.lock().unwrap() = Some((name, bytes)),
+            Err(e) => log::error!("failed to fetch audio preview of \"{name}\": {e}"),
+        });
+    }
+
+    /// Downloads an entry into a new directory and selects it once it's extracted.
+    fn download_and_select(&mut self, entry: Entry, name: String, req_fn: &'static RequestFn) {
+        let path = self.unique_download_path(&name);
+
+        // download clickpack zip & extract it
+        self.download_entry(
+            entry,
+            name,
+            req_fn,
+            DownloadTarget {
+                path,
+                mode: DownloadMode::Extract { do_select: true },
+                owns_dir: true,
+            },
+            DEFAULT_MAX_RETRIES,
+        );
+    }
+
+    /// Downloads an entry's ZIP file into `<path>/<name>.zip` without extracting it.
+    fn download_entry_zip(
+        &mut self,
+        entry: Entry,
+        name: String,
+        req_fn: &'static RequestFn,
+        path: PathBuf,
+    ) {
+        self.download_entry(
+            entry,
+            name,
+            req_fn,
+            DownloadTarget {
+                path,
+                mode: DownloadMode::Zip,
+                owns_dir: false,
+            },
+            DEFAULT_MAX_RETRIES,
+        );
+    }
+
+    /// Downloads or selects the first visible entry, depending on its status.
+    fn activate_first_entry(&mut self, req_fn: &'static RequestFn) {
+        let Some((name, entry)) = self
+            .filtered_entries
+            .get_index(0)
+            .map(|(k, v)| (k.clone(), v.clone()))
+        else {
+            return;
+        };
+        match entry.dwn_status {
+            DownloadStatus::NotDownloaded => self.download_and_select(entry, name, req_fn),
+            DownloadStatus::Downloaded {
+                path,
+                is_extracted: true,
+                ..
+            } => self.select(&name, path),
+            _ => {}
+        }
+    }
+
+    /// Sets [`ClickpackDb::select_clickpack`] and calls the [`ClickpackDb::on_select`] callback.
+    fn select(&mut self, name: &str, path: PathBuf) {
+        log::info!("selecting clickpack {path:?}");
+        if let Some(ref on_select) = self.on_select {
+            on_select(path.clone());
+        }
+        self.emit(ClickpackDbEvent::SelectPack(path.clone()));
+        self.select_clickpack = Some(path);
+        if self.selected_for_suggestion.as_deref() != Some(name) {
+            self.suggestions = self.compute_suggestions(name);
+            self.selected_for_suggestion = Some(name.to_string());
+        }
+    }
+
+    /// Names of the 3 entries whose names are most similar to `name`, best first.
+    pub fn compute_suggestions(&self, name: &str) -> Vec<String> {
+        let matcher = fuzzy_matcher::skim::SkimMatcherV2::default().ignore_case();
+        // match each word separately, the whole name rarely matches another one
+        let words: Vec<&str> = name
+            .split(['_', ' ', '-'])
+            .filter(|word| word.len() >= 3)
+            .collect();
+        let db = self.db.read().unwrap();
+        let mut scores: Vec<(i64, &String)> = db
+            .entries
+            .keys()
+            .filter(|other| *other != name && !self.blocked_entries.contains(*other))
+            .filter_map(|other| {
+                let score: i64 = words
+                    .iter()
+                    .filter_map(|word| matcher.fuzzy_match(other, word))
+                    .sum();
+                (score > 0).then_some((score, other))
+            })
+            .collect();
+        scores.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        scores
+            .into_iter()
+            .take(3)
+            .map(|(_, other)| other.clone())
+            .collect()
+    }
+
+    /// "You might also like" list of entries similar to the selected one.
+    fn suggestions_panel(&mut self, ui: &mut egui::Ui) {
+        if self.suggestions.is_empty() {
+            return;
+        }
+        let mut jump_to = None;
+        egui::CollapsingHeader::new("You might also like:")
+            .id_salt("clickpackdb_suggestions")
+            .default_open(true)
+            .show(ui, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    for name in &self.suggestions {
+                        if ui.link(self.display_name(name)).clicked() {
+                            jump_to = Some(name.clone());
+                        }
+                    }
+                });
+            });
+        if let Some(name) = jump_to {
+            self.jump_to_entry(name);
+        }
+    }
+
+    fn set_download_status(&mut self, name: &str, status: DownloadStatus) {
+        if let Some(entry) = self.db.write().unwrap().entries.get_mut(name) {
+            entry.dwn_status = status;
+        }
+        self.update_filtered_entries();
+    }
+
+    fn download_entry(
+        &mut self,
+        entry: Entry,
+        name: String,
+        req_fn: &'static RequestFn,
+        target: DownloadTarget,
+        max_retries: u8,
+    ) {
+        let cancel = CancelToken::default();
+        self.set_download_status(&name, DownloadStatus::downloading(cancel.clone()));
+        self.spawn_download(entry, name, req_fn, target, max_retries, cancel);
+    }
+
+    /// Starts the download thread of an entry whose status was already set to
+    /// [`DownloadStatus::Downloading`] with `cancel`.
+    fn spawn_download(
+        &mut self,
+        mut entry: Entry,
+        name: String,
+        req_fn: &'static RequestFn,
+        target: DownloadTarget,
+        max_retries: u8,
+        cancel: CancelToken,
+    ) {
+        self.download_targets.insert(name.clone(), target.clone());
+        let DownloadTarget {
+            path,
+            mode,
+            owns_dir,
+        } = target;
+        log::info!("downloading entry \"{name}\" to path {path:?}");
+        self.emit(ClickpackDbEvent::DownloadStarted(name.clone()));
+        let pending_update = self.pending_update.clone();
+        let reverted_updates = self.reverted_updates.clone();
+        // kept if a re-download is cancelled or fails
+        let previous_status = entry.dwn_status.clone();
+        let streaming_req_fn = self.streaming_req_fn;
+        let max_download_bps = self.config.max_download_bps;
+        let download_slots = self.download_slots.clone();
+        let progress = DownloadProgress::default();
+        self.download_progress
+            .insert(name.clone(), progress.clone());
+        // path.push(&name);
+        std::thread::spawn(move || {
+            let slot = download_slots.acquire(&cancel, |position| {
+                let mut entry = entry.clone();
+                entry.dwn_status = DownloadStatus::Queued {
+                    position,
+                    cancel: cancel.clone(),
+                };
+                pending_update.write().unwrap().insert(name.clone(), entry);
+            });
+            if slot.is_some() {
+                // restart the speed measurement, the download could have been queued
+                entry.dwn_status = DownloadStatus::downloading(cancel.clone());
+                pending_update
+                    .write()
+                    .unwrap()
+                    .insert(name.clone(), entry.clone());
+            }
+            let urls: Vec<String> = std::iter::once(&entry.url)
+                .chain(&entry.mirror_urls)
+                .cloned()
+                .collect();
+            let mut url_index = 0;
+            let mut attempt = 0;
+            let result = loop {
+                let url = &urls[url_index];
+                let result = if cancel.is_cancelled() {
+                    // cancelled while waiting for a free slot or a retry
+                    Ok(vec![])
+                } else if let Some(streaming_req_fn) = streaming_req_fn {
+                    let started_at = std::time::Instant::now();
+                    streaming_req_fn(url, &|received, total| {
+                        *progress.lock().unwrap() = (received, total);
+                        // runs inside the embedder's read loop, so sleeping slows the transfer
+                        if let Some(max_bps) = max_download_bps {
+                            throttle(started_at, received, max_bps);
+                        }
+                    })
+                } else {
+                    req_fn(url)
+                };
+                match result {
+                    Err(e)
+                        if (attempt < max_retries || url_index + 1 < urls.len())
+                            && !cancel.is_cancelled() =>
+                    {
+                        let delay = retry_delay(attempt);
+                        if attempt < max_retries {
+                            attempt += 1;
+                            log::warn!(
+                                "failed to download \"{name}\" ({e}), retrying in {delay:?} ({attempt}/{max_retries})"
+                            );
+                        } else {
+                            url_index += 1;
+                            attempt = 0;
+                            log::warn!(
+                                "failed to download \"{name}\" ({e}), trying mirror {}",
+                                urls[url_index]
+                            );
+                        }
+                        entry.dwn_status = DownloadStatus::Retrying {
+                            attempt,
+                            max: max_retries,
+                            error: e,
+                            cancel: cancel.clone(),
+                            mirror: (url_index > 0).then_some((url_index, urls.len() - 1)),
+                        };
+                        pending_update
+                            .write()
+                            .unwrap()
+                            .insert(name.clone(), entry.clone());
+                        std::thread::sleep(delay);
+                        *progress.lock().unwrap() = (0, None);
+                        entry.dwn_status = DownloadStatus::downloading(cancel.clone());
+                        pending_update
+                            .write()
+                            .unwrap()
+                            .insert(name.clone(), entry.clone());
+                    }
+                    result => break result,
+                }
+            };
+            match result {
+                Ok(_) if cancel.is_cancelled() => {
+                    log::info!("download of \"{name}\" was cancelled");
+                    if matches!(mode, DownloadMode::Update) {
+                        reverted_updates.lock().unwrap().insert(name.clone());
+                        entry.dwn_status = previous_status;
+                    } else {
+                        // only removes the directory if nothing was extracted into it
+                        if owns_dir {
+                            let _ = std::fs::remove_dir(&path);
+                        }
+                        entry.dwn_status = DownloadStatus::NotDownloaded;
+                    }
+                }
+                Ok(body)
+                    if entry
+                        .sha256
+                        .as_ref()
+                        .is_some_and(|hash| !hash.eq_ignore_ascii_case(&sha256_hex(&body))) =>
+                {
+                    log::error!("checksum mismatch for \"{name}\", not extracting");
+                    if matches!(mode, DownloadMode::Update) {
+                        reverted_updates.lock().unwrap().insert(name.clone());
+                        entry.dwn_status = previous_status;
+                    } else {
+                        if owns_dir {
+                            let _ = std::fs::remove_dir(&path);
+                        }
+                        entry.dwn_status = DownloadStatus::Error("checksum mismatch".into());
+                    }
+                }
+                Ok(body) if matches!(mode, DownloadMode::Zip) => {
+                    let zip_path = path.join(format!("{name}.zip"));
+                    log::debug!("body length: {} bytes, saving zip", body.len());
+                    let _ = std::fs::create_dir_all(&path)
+                        .map_err(|e| log::error!("create_dir_all failed: {e}"));
+                    if let Err(e) = std::fs::write(&zip_path, body) {
+                        log::error!("failed to save zip to {zip_path:?}: {e}");
+                        entry.dwn_status = DownloadStatus::Error(e.to_string());
+                    } else {
+                        entry.dwn_status = DownloadStatus::Downloaded {
+                            path: zip_path,
+                            do_select: false,
+                            is_extracted: false,
+                        };
+                        log::info!("\"{name}\" is {}", entry.dwn_status);
+                    }
+                }
+                Ok(body) => {
+                    log::debug!("body length: {} bytes, extracting zip", body.len());
+                    let size = body.len();
+                    if matches!(mode, DownloadMode::Update) && owns_dir {
+                        // sounds removed in the new version would stay behind otherwise
+                        let _ = std::fs::remove_dir_all(&path)
+                            .map_err(|e| log::error!("failed to remove old version: {e}"));
+                    }
+                    let _ = std::fs::create_dir_all(&path)
+                        .map_err(|e| log::error!("create_dir_all failed: {e}"));
+                    if let Err(e) = zip_extract::extract(Cursor::new(body), &path, true) {
+                        log::error!("failed to extract zip to {path:?}: {e}");
+                        entry.dwn_status = DownloadStatus::Error(e.to_string());
+                    } else {
+                        if entry.is_local {
+                            // sizes of entries imported from a URL aren't known beforehand
+                            entry.size = size;
+                            entry.uncompressed_size = dir_size(&path);
+                        }
+                        entry.dwn_status = DownloadStatus::Downloaded {
+                            path,
+                            do_select: matches!(mode, DownloadMode::Extract { do_select: true }),
+                            is_extracted: true,
+                        };
+                        log::info!("\"{name}\" is {}", entry.dwn_status);
                     }
-                };
-                log::info!("loaded {} entries", db.read().unwrap().entries.len());
-                *status.write().unwrap() = Status::Loaded { did_filter: false };
-            }
-            Err(e) => {
-                log::error!("failed to GET database: {e}");
-                *status.write().unwrap() = Status::Error(e.to_string());
+                }
+                Err(e) => {
+                    entry.dwn_status = DownloadStatus::Error(e);
+                    log::error!("\"{name}\": {}", entry.dwn_status);
+                }
             }
+            pending_update.write().unwrap().insert(name, entry);
         });
     }
 
-    fn update_filtered_entries(&mut self) {
-        self.filtered_entries = self.db.read().unwrap().entries.clone();
-
-        // handle tags
-        if self.tags.has_any() {
-            self.filtered_entries.retain(|_, v| {
-                if self.tags.noise && !v.has_noise {
-                    return false;
-                }
-                if self.tags.downloaded
-                    && !matches!(v.dwn_status, DownloadStatus::Downloaded { .. })
-                {
-                    return false;
+    /// Downloads every visible entry that isn't downloaded yet. Downloads past
+    /// the concurrency limit wait for a free slot.
+    fn download_all_button(&mut self, ui: &mut egui::Ui, req_fn: &'static RequestFn) {
+        let not_downloaded = || {
+            self.filtered_entries
+                .iter()
+                .filter(|(_, e)| matches!(e.dwn_status, DownloadStatus::NotDownloaded))
+        };
+        let (nr_packs, total_size) =
+            not_downloaded().fold((0, 0), |(n, size), (_, e)| (n + 1, size + e.size));
+        if nr_packs == 0 {
+            return;
+        }
+        if ui
+            .button("⬇ Download all")
+            .on_hover_text(format!(
+                "This will download {nr_packs} pack{} ({})",
+                if nr_packs == 1 { "" } else { "s" },
+                format_size(total_size, DECIMAL)
+            ))
+            .clicked()
+        {
+            let to_download: Vec<(String, Entry)> = not_downloaded()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+            log::info!("downloading {nr_packs} visible clickpacks");
+            let cancel_tokens: Vec<CancelToken> =
+                to_download.iter().map(|_| CancelToken::default()).collect();
+            // set all statuses at once, filtering the table for every download
+            // would take quadratic time
+            let mut db = self.db.write().unwrap();
+            for ((name, _), cancel) in to_download.iter().zip(&cancel_tokens) {
+                if let Some(entry) = db.entries.get_mut(name) {
+                    entry.dwn_status = DownloadStatus::downloading(cancel.clone());
                 }
-                true
-            });
+            }
+            drop(db);
+            self.update_filtered_entries();
+            for ((name, entry), cancel) in to_download.into_iter().zip(cancel_tokens) {
+                let path = self.unique_download_path(&name);
+                self.spawn_download(
+                    entry,
+                    name,
+                    req_fn,
+                    DownloadTarget {
+                        path,
+                        mode: DownloadMode::Extract { do_select: false },
+                        owns_dir: true,
+                    },
+                    DEFAULT_MAX_RETRIES,
+                    cancel,
+                );
+            }
         }
+    }
 
-        // fuzzy sort with search query
-        if !self.search_query.is_empty() {
-            let matcher = fuzzy_matcher::skim::SkimMatcherV2::default();
-            self.filtered_entries.sort_by_cached_key(|k, _| {
-                std::cmp::Reverse(matcher.fuzzy_match(k, &self.search_query).unwrap_or(0))
-            });
+    /// Adds the current search query to the front of the search history.
+    fn push_search_history(&mut self) {
+        let query = self.search_query.trim();
+        if query.is_empty() {
+            return;
         }
+        self.search_history.retain(|q| q != query);
+        self.search_history.push_front(query.to_string());
+        self.search_history.truncate(SEARCH_HISTORY_LEN);
     }
 
-    #[cfg(feature = "live")]
-    pub fn mark_downloaded(&mut self, name: &str, path: PathBuf, downloaded: bool) {
-        let update_status = |status: &mut DownloadStatus| {
-            if downloaded {
-                *status = DownloadStatus::Downloaded {
-                    path: path.clone(),
-                    do_select: false,
-                };
-            } else {
-                *status = DownloadStatus::NotDownloaded;
+    /// Shows the recent searches below the search bar while it is focused and empty.
+    fn search_history_popup(&mut self, ui: &mut egui::Ui, search_response: &egui::Response) {
+        let popup_id = ui.make_persistent_id("clickpackdb_search_history");
+        if search_response.has_focus() && self.search_query.is_empty() {
+            if !self.search_history.is_empty() {
+                ui.memory_mut(|mem| mem.open_popup(popup_id));
             }
-        };
+        } else if search_response.changed() {
+            ui.memory_mut(|mem| mem.close_popup());
+        }
+        let mut picked = None;
+        egui::popup_below_widget(
+            ui,
+            popup_id,
+            search_response,
+            egui::PopupCloseBehavior::CloseOnClick,
+            |ui| {
+                ui.weak("Recent searches");
+                for query in &self.search_history {
+                    if ui.selectable_label(false, query).clicked() {
+                        picked = Some(query.clone());
+                    }
+                }
+            },
+        );
+        if let Some(query) = picked {
+            self.set_search_query(&query);
+        }
+    }
 
-        if let Some(entry) = self.db.write().unwrap().entries.get_mut(name) {
-            update_status(&mut entry.dwn_status);
+    fn export_urls_button(&mut self, ui: &mut egui::Ui) {
+        if !self.config.show_export_button {
+            return;
         }
-        if let Some(entry) = self.filtered_entries.get_mut(name) {
-            update_status(&mut entry.dwn_status);
+        if let Some((nr_urls, until)) = self.copy_ack_until {
+            if std::time::Instant::now() < until {
+                ui.label(format!("Copied {nr_urls} URLs!"));
+                ui.ctx()
+                    .request_repaint_after(until - std::time::Instant::now());
+                return;
+            }
+            self.copy_ack_until = None;
+        }
+        if ui
+            .button("Export URLs")
+            .on_hover_text("Copy the URLs of all visible clickpacks, one per line")
+            .clicked()
+        {
+            let urls: Vec<&str> = self
+                .filtered_entries
+                .values()
+                .map(|e| e.url.as_str())
+                .collect();
+            ui.ctx().copy_text(urls.join("\n"));
+            self.copy_ack_until = Some((
+                urls.len(),
+                std::time::Instant::now() + std::time::Duration::from_secs(2),
+            ));
         }
     }
 
-    fn update_pending_update(&mut self) {
-        let mut is_empty = true;
-        for (k, v) in self.pending_update.read().unwrap().iter() {
-            is_empty = false;
-            self.db
-                .write()
-                .unwrap()
-                .entries
-                .insert(k.clone(), v.clone());
-            if self.filtered_entries.contains_key(k) {
-                self.filtered_entries.insert(k.clone(), v.clone());
+    /// Sets the status to [`Status::Loading`] and starts loading the database.
+    fn start_loading(&mut self, req_fn: &'static RequestFn) {
+        *self.status.write().unwrap() = Status::Loading;
+        if let Some(ref path) = self.local_db_path {
+            Self::load_database_from_path(self.status.clone(), self.db.clone(), path.clone());
+        } else {
+            Self::load_database(
+                self.status.clone(),
+                self.db.clone(),
+                req_fn,
+                self.config.database_url.clone(),
+                self.config.clone(),
+                // the embedder manages the files then
+                !self.state_from_embedder,
+            );
+        }
+    }
+
+    /// When the database was last refreshed successfully. The initial load and
+    /// failed refreshes don't count.
+    pub fn last_refresh_time(&self) -> Option<std::time::Instant> {
+        self.last_refresh_time
+    }
+
+    /// Fetches the database again right away, without waiting for the next
+    /// [`ClickpackDb::show`]. Does nothing if the database is already loading.
+    pub fn force_refresh(&mut self, req_fn: &'static RequestFn) {
+        if matches!(*self.status.read().unwrap(), Status::Loading) {
+            return;
+        }
+        self.show_update_banner = false;
+        self.is_refreshing = true;
+        self.start_loading(req_fn);
+    }
+
+    fn refresh_button(&mut self, ui: &mut egui::Ui, req_fn: &'static RequestFn) {
+        let cooldown = self
+            .last_refresh_time
+            .map(|time| REFRESH_COOLDOWN.saturating_sub(time.elapsed()))
+            .filter(|cooldown| !cooldown.is_zero() && !self.has_error());
+        if let Some(cooldown) = cooldown {
+            // enable the button again once the cooldown is over
+            ui.ctx().request_repaint_after(cooldown);
+        }
+        if ui
+            .add_enabled(cooldown.is_none(), egui::Button::new("🔄 Refresh"))
+            .on_hover_text("Fetch the database again")
+            .on_disabled_hover_text(format!(
+                "Wait {} seconds before refreshing again",
+                cooldown.unwrap_or_default().as_secs() + 1
+            ))
+            .clicked()
+        {
+            self.force_refresh(req_fn);
+        }
+    }
+
+    #[cfg(not(feature = "live"))]
+    fn settings_menu(&mut self, ui: &mut egui::Ui) {
+        ui.menu_button("⚙", |ui| {
+            if ui
+                .button("Clear cache")
+                .on_hover_text("Delete all clickpacks downloaded into the download directory")
+                .clicked()
+            {
+                self.clear_download_cache();
+                ui.close_menu();
+            }
+        })
+        .response
+        .on_hover_text("Settings");
+    }
+
+    fn alphabet_bar_button(&mut self, ui: &mut egui::Ui) {
+        if ui
+            .selectable_label(self.show_alphabet_bar, "A–Z")
+            .on_hover_text("Filter clickpacks by their first letter")
+            .clicked()
+        {
+            self.show_alphabet_bar = !self.show_alphabet_bar;
+            // don't keep filtering by a letter that can't be seen
+            if !self.show_alphabet_bar && self.tags.name_starts_with.take().is_some() {
+                self.update_filtered_entries();
             }
         }
-        if !is_empty {
-            self.pending_update.write().unwrap().clear();
+    }
+
+    /// Row of letter buttons that filter entries by the first letter of their name.
+    fn alphabet_bar(&mut self, ui: &mut egui::Ui) {
+        if !self.show_alphabet_bar {
+            return;
         }
-        for path in self.pending_clickpack_delete.drain(..) {
-            if let Err(e) = std::fs::remove_dir_all(&path) {
-                log::error!("failed to delete clickpack directory {path:?}: {e}");
+        ui.horizontal_wrapped(|ui| {
+            ui.style_mut().spacing.item_spacing.x = 2.0;
+            if ui
+                .selectable_label(self.tags.name_starts_with.is_none(), "All")
+                .clicked()
+            {
+                self.tags.name_starts_with = None;
+                self.update_filtered_entries();
+            }
+            for letter in 'A'..='Z' {
+                let selected = self.tags.name_starts_with == Some(letter);
+                if ui.selectable_label(selected, letter.to_string()).clicked() {
+                    self.tags.name_starts_with = Some(letter);
+                    self.update_filtered_entries();
+                }
             }
+        });
+    }
+
+    fn db_age_label(&self, ui: &mut egui::Ui) {
+        let updated_at_unix = self.db.read().unwrap().updated_at_unix;
+        if updated_at_unix == 0 {
+            return;
+        }
+        let text = format!("Updated {}", format_db_age(updated_at_unix));
+        if secs_since_unix(updated_at_unix) > 60 * 60 * 24 * 30 {
+            ui.colored_label(ui.visuals().warn_fg_color, text)
+                .on_hover_text("The database hasn't been updated in over 30 days");
+        } else {
+            ui.weak(text);
         }
     }
 
-    pub fn show(
-        &mut self,
-        ui: &mut egui::Ui,
-        req_fn: &'static RequestFn,
-        #[cfg(not(feature = "live"))] pick_folder: &'static PickFolderFn,
-    ) {
-        let mut status = self.status.read().unwrap().clone();
-        match status {
-            Status::NotLoaded => {
-                (*self.status.write().unwrap(), status) = (Status::Loading, Status::Loading);
-                Self::load_database(self.status.clone(), self.db.clone(), req_fn);
+    /// Shows the active official tag filters as chips that can be clicked to remove them.
+    fn official_tag_chips(&mut self, ui: &mut egui::Ui) {
+        if self.tags.official.is_empty() {
+            return;
+        }
+        let mut active: Vec<String> = self.tags.official.iter().cloned().collect();
+        active.sort();
+        ui.horizontal_wrapped(|ui| {
+            ui.style_mut().spacing.item_spacing.x = 5.0;
+            ui.label("Tags:");
+            for tag in active {
+                let chip =
+                    egui::Button::new(tag_text(ui, Color32::LIGHT_RED, "●", &format!(" {tag} ✕")))
+                        .small()
+                        .rounding(8.0);
+                if ui.add(chip).on_hover_text("Remove this filter").clicked() {
+                    self.tags.official.remove(&tag);
+                    self.update_filtered_entries();
+                }
             }
-            Status::Loading => {
-                ui.horizontal(|ui| {
-                    ui.add(egui::Spinner::new());
-                    ui.label("Loading database…");
-                });
+        });
+    }
+
+    fn size_filter(&mut self, ui: &mut egui::Ui) {
+        const BYTES_PER_MB: f64 = 1_000_000.0;
+        let to_mb = |bytes: Option<usize>| bytes.map_or(0.0, |b| b as f64 / BYTES_PER_MB);
+        let to_bytes = |mb: f64| (mb > 0.0).then_some((mb * BYTES_PER_MB) as usize);
+
+        let (mut min_mb, mut max_mb) = (
+            to_mb(self.tags.size_min_bytes),
+            to_mb(self.tags.size_max_bytes),
+        );
+        let any_formatter = |n: f64, _| {
+            if n == 0.0 {
+                "any".to_string()
+            } else {
+                format!("{n:.1} MB")
             }
-            Status::Error(ref e) => {
-                ui.colored_label(egui::Color32::RED, format!("Error loading database: {e}"));
+        };
+        ui.horizontal(|ui| {
+            ui.style_mut().spacing.item_spacing.x = 5.0;
+            ui.label("Uncompressed size:");
+            let min_changed = ui
+                .add(
+                    egui::DragValue::new(&mut min_mb)
+                        .range(0.0..=f64::MAX)
+                        .speed(0.1)
+                        .custom_formatter(any_formatter),
+                )
+                .on_hover_text("Minimum size in MB")
+                .changed();
+            ui.label("–");
+            let max_changed = ui
+                .add(
+                    egui::DragValue::new(&mut max_mb)
+                        .range(0.0..=f64::MAX)
+                        .speed(0.1)
+                        .custom_formatter(any_formatter),
+                )
+                .on_hover_text("Maximum size in MB")
+                .changed();
+            if min_changed || max_changed {
+                self.tags.size_min_bytes = to_bytes(min_mb);
+                self.tags.size_max_bytes = to_bytes(max_mb);
+                self.update_filtered_entries();
             }
-            Status::Loaded { did_filter } => {
-                if !did_filter {
-                    self.update_filtered_entries();
-                    #[cfg(feature = "live")]
-                    {
-                        self.has_refreshed = true;
+        });
+    }
+
+    fn min_downloads_filter(&mut self, ui: &mut egui::Ui) {
+        let max_downloads = self
+            .db
+            .read()
+            .unwrap()
+            .entries
+            .values()
+            .map(|entry| entry.downloads)
+            .max()
+            .unwrap_or(0);
+        let mut min_downloads = self.tags.min_downloads.unwrap_or(0);
+        ui.horizontal(|ui| {
+            ui.style_mut().spacing.item_spacing.x = 5.0;
+            ui.label("Min downloads:");
+            let changed = ui
+                .add(
+                    egui::Slider::new(&mut min_downloads, 0..=max_downloads).custom_formatter(
+                        |n, _| {
+                            if n == 0.0 {
+                                "any".to_string()
+                            } else {
+                                format!("{n:.0}")
+                            }
+                        },
+                    ),
+                )
+                .on_hover_text("Hide clickpacks with fewer downloads")
+                .changed();
+            if changed {
+                self.tags.min_downloads = (min_downloads > 0).then_some(min_downloads);
+                self.update_filtered_entries();
+            }
+        });
+    }
+
+    fn compatibility_filter(&mut self, ui: &mut egui::Ui) {
+        let systems = self.all_compatible_systems();
+        if systems.is_empty() {
+            return;
+        }
+        ui.separator();
+        egui::CollapsingHeader::new("Compatible with…")
+            .id_salt("clickpackdb_compatible_with")
+            .default_open(!self.tags.compatible_with.is_empty())
+            .show(ui, |ui| {
+                for system in systems {
+                    let mut checked = self.tags.compatible_with.contains(&system);
+                    let job = tag_text(ui, Color32::LIGHT_GREEN, "🎮", &format!(" {system}"));
+                    if ui.checkbox(&mut checked, job).changed() {
+                        if checked {
+                            self.tags.compatible_with.insert(system);
+                        } else {
+                            self.tags.compatible_with.remove(&system);
+                        }
+                        self.update_filtered_entries();
                     }
-                    *self.status.write().unwrap() = Status::Loaded { did_filter: true };
                 }
+            });
+    }
+
+    fn sort_buttons(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.style_mut().spacing.item_spacing.x = 5.0;
+            ui.label("Sort by:");
+            for column in SortColumn::ALL {
+                let (text, selected) = match self.sort_order {
+                    Some((c, true)) if c == column => (format!("{} ⏶", column.label()), true),
+                    Some((c, false)) if c == column => (format!("{} ⏷", column.label()), true),
+                    _ => (column.label().to_string(), false),
+                };
+                if ui
+                    .selectable_label(selected, text)
+                    .on_hover_text("Click to cycle ascending, descending and unsorted")
+                    .clicked()
+                {
+                    // cycle: unsorted -> ascending -> descending -> unsorted
+                    self.sort_order = match self.sort_order {
+                        Some((c, true)) if c == column => Some((column, false)),
+                        Some((c, false)) if c == column => None,
+                        _ => Some((column, true)),
+                    };
+                    self.update_filtered_entries();
+                }
+            }
+            ui.separator();
+            let mut grouped = self.view_mode == ViewMode::GroupedByAuthor;
+            if ui
+                .toggle_value(&mut grouped, "Group by author")
+                .on_hover_text("Show a collapsible section for each author")
+                .changed()
+            {
+                self.view_mode = if grouped {
+                    ViewMode::GroupedByAuthor
+                } else {
+                    ViewMode::Flat
+                };
+            }
+        });
+    }
+
+    /// Column with the download buttons, see [`ClickpackDbConfig::column_widths`].
+    fn actions_column(&self) -> Column {
+        self.config
+            .actions_column_width
+            .map_or(Column::auto(), Column::exact)
+    }
+
+    /// Height of the row of an entry. Expanded descriptions make rows taller.
+    fn row_height(
+        &self,
+        ui: &egui::Ui,
+        name: &str,
+        entry: &Entry,
+        row_height: f32,
+        description_width: f32,
+    ) -> f32 {
+        match entry.description {
+            Some(ref description)
+                if !description.is_empty() && self.expanded_entries.contains(name) =>
+            {
+                row_height
+                    + ui.spacing().item_spacing.y
+                    + Self::description_height(ui, description, description_width)
             }
+            _ => row_height,
         }
-        self.update_pending_update();
-        ui.add_enabled_ui(
-            !matches!(status, Status::NotLoaded | Status::Loading),
-            |ui| {
-                #[cfg(not(feature = "live"))]
-                self.show_table(ui, req_fn, pick_folder);
-                #[cfg(feature = "live")]
-                self.show_table(ui, req_fn);
-            },
-        );
     }
 
-    fn download_entry(
+    fn table_row(
         &mut self,
-        mut entry: Entry,
-        name: String,
+        row: &mut TableRow,
+        name: &str,
+        entry: &Entry,
+        show_author: bool,
         req_fn: &'static RequestFn,
-        path: PathBuf,
-        do_select: bool,
+        #[cfg(not(feature = "live"))] pick_folder: &'static PickFolderFn,
     ) {
-        log::info!("downloading entry \"{name}\" to path {path:?}");
-        let pending_update = self.pending_update.clone();
-        // path.push(&name);
-        std::thread::spawn(move || {
-            match req_fn(&entry.url) {
-                Ok(body) => {
-                    log::debug!("body length: {} bytes, extracting zip", body.len());
-                    if let Err(e) = zip_extract::extract(Cursor::new(body), &path, true) {
-                        log::error!("failed to extract zip to {path:?}: {e}");
-                        entry.dwn_status = DownloadStatus::Error(e.to_string());
-                    } else {
-                        log::info!("successfully extracted zip to {path:?}");
-                        entry.dwn_status = DownloadStatus::Downloaded { path, do_select };
-                    }
-                }
-                Err(e) => {
-                    entry.dwn_status = DownloadStatus::Error(e);
+        row.col(|ui| {
+            self.paint_highlight(ui, name);
+            let description_width = self.config.name_column_width - ui.spacing().item_spacing.x;
+            self.name_cell(ui, name, entry, description_width);
+        });
+        if show_author {
+            row.col(|ui| {
+                self.paint_highlight(ui, name);
+                let Some(ref author) = entry.author else {
+                    return;
+                };
+                if ui
+                    .link(author)
+                    .on_hover_text("Show all clickpacks by this author")
+                    .clicked()
+                {
+                    self.search_query.clone_from(author);
+                    self.update_filtered_entries();
                 }
-            }
-            pending_update.write().unwrap().insert(name, entry);
+            });
+        }
+        row.col(|ui| {
+            self.paint_highlight(ui, name);
+            #[cfg(not(feature = "live"))]
+            self.manage_row(ui, entry, name, req_fn, pick_folder);
+            #[cfg(feature = "live")]
+            self.manage_row(ui, entry, name, req_fn);
         });
+        for column in &self.extra_columns {
+            row.col(|ui| {
+                self.paint_highlight(ui, name);
+                (column.render)(ui, name, entry);
+            });
+        }
     }
 
-    fn refresh_button(&mut self, ui: &mut egui::Ui) {
-        if ui
-            .button("🔄 Refresh")
-            .on_hover_text("Fetch the database again")
-            .clicked()
-        {
-            *self.status.write().unwrap() = Status::NotLoaded;
+    /// Lists the filtered entries in a collapsible section per author, for
+    /// [`ViewMode::GroupedByAuthor`].
+    fn grouped_view(
+        &mut self,
+        ui: &mut egui::Ui,
+        row_height: f32,
+        description_width: f32,
+        req_fn: &'static RequestFn,
+        #[cfg(not(feature = "live"))] pick_folder: &'static PickFolderFn,
+    ) {
+        // keep the order of the table within and between the groups
+        let mut groups: IndexMap<String, Vec<(String, Entry)>> = IndexMap::new();
+        for (name, entry) in &self.filtered_entries {
+            let author = entry
+                .author
+                .clone()
+                .unwrap_or_else(|| "Unknown author".to_string());
+            groups
+                .entry(author)
+                .or_default()
+                .push((name.clone(), entry.clone()));
         }
+
+        egui::ScrollArea::vertical()
+            .id_salt("clickpackdb_groups")
+            .show(ui, |ui| {
+                for (author, entries) in groups {
+                    let expanded = self.expanded_groups.contains(&author);
+                    let response =
+                        egui::CollapsingHeader::new(format!("{author} ({})", entries.len()))
+                            .id_salt(("clickpackdb_group", &author))
+                            .open(Some(expanded))
+                            .show(ui, |ui| {
+                                let row_heights: Vec<f32> = entries
+                                    .iter()
+                                    .map(|(name, entry)| {
+                                        self.row_height(
+                                            ui,
+                                            name,
+                                            entry,
+                                            row_height,
+                                            description_width,
+                                        )
+                                    })
+                                    .collect();
+                                let mut table = TableBuilder::new(ui)
+                                    .id_salt(("clickpackdb_group_table", &author))
+                                    .vscroll(false)
+                                    .column(Column::exact(self.config.name_column_width))
+                                    .column(self.actions_column());
+                                for column in &self.extra_columns {
+                                    table = table.column(Column::exact(column.width));
+                                }
+                                table.striped(true).body(|body| {
+                                    body.heterogeneous_rows(row_heights.into_iter(), |mut row| {
+                                        let Some((name, entry)) = entries.get(row.index()) else {
+                                            return;
+                                        };
+                                        self.table_row(
+                                            &mut row,
+                                            name,
+                                            entry,
+                                            false,
+                                            req_fn,
+                                            #[cfg(not(feature = "live"))]
+                                            pick_folder,
+                                        );
+                                    });
+                                });
+                            });
+                    if response.header_response.clicked() {
+                        if expanded {
+                            self.expanded_groups.remove(&author);
+                        } else {
+                            self.expanded_groups.insert(author);
+                        }
+                    }
+                }
+            });
     }
 
     fn show_table(
@@ -329,74 +4020,328 @@ impl ClickpackDb {
             .size
             .max(ui.spacing().interact_size.y);
 
-        TableBuilder::new(ui)
-            .column(Column::exact(200.0))
-            .column(Column::auto())
+        self.update_banner(ui, req_fn);
+        self.whats_new_panel(ui);
+        self.official_tag_chips(ui);
+        self.alphabet_bar(ui);
+        self.sort_buttons(ui);
+
+        let page_size = self.config.page_size.max(1);
+        let nr_pages = self.filtered_entries.len().div_ceil(page_size).max(1);
+        if let Some(index) = self
+            .scroll_to_entry
+            .as_ref()
+            .and_then(|name| self.filtered_entries.get_index_of(name))
+        {
+            if self.page != index / page_size {
+                self.page = index / page_size;
+                self.update_visible_entries();
+            }
+        }
+        let page_start = self.page * page_size;
+
+        // don't waste space on the author column if nobody has an author
+        let show_author = self.filtered_entries.values().any(|e| e.author.is_some());
+        let grouped = self.view_mode == ViewMode::GroupedByAuthor && show_author;
+        // the groups have their own authors
+        let show_author = show_author && !grouped;
+
+        // the grouped view lists the entries below the header instead
+        let row_height = text_height * self.config.row_height_multiplier;
+        let description_width = self.config.name_column_width - ui.spacing().item_spacing.x;
+        let row_heights: Vec<f32> = if grouped {
+            vec![]
+        } else {
+            self.visible_entries
+                .iter()
+                .map(|(name, entry)| {
+                    self.row_height(ui, name, entry, row_height, description_width)
+                })
+                .collect()
+        };
+
+        let focus_search = ui.ctx().input_mut(|i| {
+            i.consume_shortcut(&egui::KeyboardShortcut::new(
+                egui::Modifiers::COMMAND,
+                egui::Key::F,
+            ))
+        });
+        // only take Escape from the search bar, not from dialogs and popups
+        let search_id = ui.make_persistent_id("clickpackdb_search");
+        let search_has_focus = focus_search || ui.ctx().memory(|m| m.has_focus(search_id));
+        if search_has_focus
+            && !self.search_query.is_empty()
+            && ui
+                .ctx()
+                .input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape))
+        {
+            self.search_query.clear();
+            self.update_filtered_entries();
+        }
+
+        let mut table = TableBuilder::new(ui)
+            .id_salt(("clickpackdb_table", show_author))
+            .column(Column::exact(self.config.name_column_width));
+        if std::mem::take(&mut self.scroll_to_top) {
+            table = table.scroll_to_row(0, Some(egui::Align::TOP));
+        }
+        if let Some(name) = self.scroll_to_entry.take() {
+            if let Some(index) = self.filtered_entries.get_index_of(&name) {
+                table = table.scroll_to_row(index - page_start, Some(egui::Align::Center));
+            }
+        }
+        if show_author {
+            table = table.column(Column::auto());
+        }
+        table = table.column(self.actions_column());
+        for column in &self.extra_columns {
+            table = table.column(Column::exact(column.width));
+        }
+        // rows can change the entries while rendering, in which case
+        // `visible_entries` is rebuilt and this copy is stale
+        let visible_entries = std::mem::take(&mut self.visible_entries);
+        let visible_generation = self.visible_generation;
+        let scroll_output = table
             .striped(true)
             .header(30.0, |mut header| {
                 header.col(|ui| {
                     // ui.heading("Name");
                     let nr_clickpacks = self.db.read().unwrap().entries.len();
-                    ui.horizontal_centered(|ui| {
+                    // right to left, so the search bar fills the space left by the case toggle
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui
+                            .selectable_label(self.case_sensitive, "Aa")
+                            .on_hover_text("Match case")
+                            .clicked()
+                        {
+                            self.case_sensitive = !self.case_sensitive;
+                            self.update_filtered_entries();
+                        }
                         let textedit = egui::TextEdit::singleline(&mut self.search_query)
+                            .id(search_id)
                             .hint_text(format!("🔎 Search in {nr_clickpacks} clickpacks"));
-                        if ui.add(textedit).changed() {
-                            self.update_filtered_entries();
+                        let response = ui.add(textedit).on_hover_text(
+                            "Ctrl+F to focus, Escape to clear, \
+                            Enter to download or select the first result\n\
+                            Filters: noise:yes, noise:no, size:>5mb, size:<500kb, \
+                            downloads:>1000, downloads:<=50",
+                        );
+                        if focus_search {
+                            response.request_focus();
+                        }
+                        if response.changed() {
+                            self.page = 0;
+                            if self.config.search_debounce_ms == 0 {
+                                self.update_filtered_entries();
+                            } else {
+                                self.last_search_change = Some(std::time::Instant::now());
+                            }
+                        }
+                        if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                            // don't activate a result of the previous query
+                            if self.last_search_change.take().is_some() {
+                                self.update_filtered_entries();
+                            }
+                            self.activate_first_entry(req_fn);
+                        }
+                        if response.lost_focus() {
+                            self.push_search_history();
                         }
+                        self.search_history_popup(ui, &response);
                     });
                 });
+                if show_author {
+                    header.col(|ui| {
+                        ui.horizontal_centered(|ui| {
+                            ui.strong("Author");
+                        });
+                    });
+                }
                 header.col(|ui| {
                     ui.horizontal_centered(|ui| {
                         ui.style_mut().spacing.item_spacing.x = 5.0;
-                        self.refresh_button(ui);
+                        self.refresh_button(ui, req_fn);
+                        #[cfg(not(feature = "live"))]
+                        self.settings_menu(ui);
+                        self.alphabet_bar_button(ui);
+                        self.download_all_button(ui, req_fn);
+                        self.export_urls_button(ui);
                         egui::ComboBox::new("manage_tags_combobox", "")
                             .selected_text("Tags…")
                             .show_ui(ui, |ui| {
-                                let job = tag_text(ui, Color32::KHAKI, "🎧", " Has noise");
+                                if ui
+                                    .add_enabled(
+                                        self.tags.has_any(),
+                                        egui::Button::new("Reset filters"),
+                                    )
+                                    .clicked()
+                                {
+                                    self.tags = Tags::default();
+                                    self.update_filtered_entries();
+                                }
+                                ui.separator();
+                                let job = tag_text(ui, self.theme.noise_color, "🎧", " Has noise");
                                 if ui.checkbox(&mut self.tags.noise, job).changed() {
                                     self.update_filtered_entries();
                                 }
-                                let job = tag_text(ui, Color32::LIGHT_GREEN, "✅", " Downloaded");
+                                let job =
+                                    tag_text(ui, Color32::LIGHT_BLUE, "🔉", " Has softclicks");
+                                if ui.checkbox(&mut self.tags.softclicks, job).changed() {
+                                    self.update_filtered_entries();
+                                }
+                                let job = tag_text(ui, Color32::ORANGE, "🔊", " Has hardclicks");
+                                if ui.checkbox(&mut self.tags.hardclicks, job).changed() {
+                                    self.update_filtered_entries();
+                                }
+                                let job =
+                                    tag_text(ui, self.theme.downloaded_color, "✅", " Downloaded");
                                 if ui.checkbox(&mut self.tags.downloaded, job).changed() {
                                     self.update_filtered_entries();
                                 }
-                            })
+                                let job = tag_text(ui, Color32::GOLD, "★", " Favorites");
+                                if ui.checkbox(&mut self.tags.favorites, job).changed() {
+                                    self.update_filtered_entries();
+                                }
+                                let job = tag_text(ui, Color32::YELLOW, "⭐", " Featured only");
+                                if ui.checkbox(&mut self.tags.featured, job).changed() {
+                                    self.update_filtered_entries();
+                                }
+                                let job =
+                                    tag_text(ui, Color32::LIGHT_GRAY, "⚖", " License: CC0 only");
+                                if ui.checkbox(&mut self.tags.cc0_only, job).changed() {
+                                    self.update_filtered_entries();
+                                }
+                                self.size_filter(ui);
+                                self.min_downloads_filter(ui);
+                                if let Some(threshold) = self.popular_threshold {
+                                    let job = tag_text(
+                                        ui,
+                                        Color32::GOLD,
+                                        "🔥",
+                                        &format!(" Highlight popular ({threshold}+ downloads)"),
+                                    );
+                                    ui.checkbox(&mut self.highlight_popular, job);
+                                }
+                                let official_tags = self.all_official_tags();
+                                if !official_tags.is_empty() {
+                                    ui.separator();
+                                }
+                                for tag in official_tags {
+                                    let mut checked = self.tags.official.contains(&tag);
+                                    let job =
+                                        tag_text(ui, Color32::LIGHT_RED, "●", &format!(" {tag}"));
+                                    if ui.checkbox(&mut checked, job).changed() {
+                                        if checked {
+                                            self.tags.official.insert(tag);
+                                        } else {
+                                            self.tags.official.remove(&tag);
+                                        }
+                                        self.update_filtered_entries();
+                                    }
+                                }
+                                self.compatibility_filter(ui);
+                                let custom_tags = self.all_custom_tags();
+                                if !custom_tags.is_empty() {
+                                    ui.separator();
+                                }
+                                for tag in custom_tags {
+                                    let mut checked = self.tags.custom.contains(&tag);
+                                    let job =
+                                        tag_text(ui, Color32::LIGHT_BLUE, "🏷", &format!(" {tag}"));
+                                    if ui.checkbox(&mut checked, job).changed() {
+                                        if checked {
+                                            self.tags.custom.insert(tag);
+                                        } else {
+                                            self.tags.custom.remove(&tag);
+                                        }
+                                        self.update_filtered_entries();
+                                    }
+                                }
+                            });
+                        self.db_age_label(ui);
                     });
                 });
+                for column in &self.extra_columns {
+                    header.col(|ui| {
+                        ui.horizontal_centered(|ui| {
+                            ui.strong(&column.header);
+                        });
+                    });
+                }
             })
             .body(|body| {
-                body.rows(text_height * 1.5, self.filtered_entries.len(), |mut row| {
-                    let row_index = row.index();
-                    let Some(entry) = self.filtered_entries.get_index(row_index) else {
+                body.heterogeneous_rows(row_heights.into_iter(), |mut row| {
+                    let Some((name, entry)) = visible_entries.get(row.index()) else {
                         return;
                     };
-                    let name = entry.0.clone();
-                    let entry = entry.1.clone();
-                    row.col(|ui| {
-                        ui.horizontal(|ui| {
-                            ui.style_mut().spacing.item_spacing.x = 5.0;
-                            ui.add(egui::Label::new(name.replace('_', " ")).wrap());
-                            ui.style_mut().spacing.item_spacing.x = 5.0;
-                            if entry.has_noise {
-                                ui.colored_label(Color32::KHAKI, "🎧")
-                                    .on_hover_text("This clickpack has a noise file")
-                                    .on_hover_cursor(egui::CursorIcon::Default);
-                            }
-                            if matches!(entry.dwn_status, DownloadStatus::Downloaded { .. }) {
-                                ui.colored_label(Color32::LIGHT_GREEN, "✅")
-                                    .on_hover_text("Downloaded")
-                                    .on_hover_cursor(egui::CursorIcon::Default);
-                            }
-                        });
-                    });
-                    row.col(|ui| {
+                    self.table_row(
+                        &mut row,
+                        name,
+                        entry,
+                        show_author,
+                        req_fn,
                         #[cfg(not(feature = "live"))]
-                        self.manage_row(ui, entry, name, req_fn, pick_folder);
-                        #[cfg(feature = "live")]
-                        self.manage_row(ui, entry, name, req_fn);
-                    });
+                        pick_folder,
+                    );
+                });
+            });
+
+        if self.visible_generation == visible_generation {
+            self.visible_entries = visible_entries;
+        }
+
+        if grouped {
+            self.grouped_view(
+                ui,
+                row_height,
+                description_width,
+                req_fn,
+                #[cfg(not(feature = "live"))]
+                pick_folder,
+            );
+        }
+
+        self.show_scroll_to_top = scroll_output.state.offset.y > 200.0;
+        if self.show_scroll_to_top {
+            let margin = ui.spacing().item_spacing;
+            egui::Area::new(ui.id().with("clickpackdb_scroll_to_top"))
+                .order(egui::Order::Foreground)
+                .pivot(egui::Align2::RIGHT_BOTTOM)
+                .fixed_pos(scroll_output.inner_rect.right_bottom() - margin)
+                .show(ui.ctx(), |ui| {
+                    if ui
+                        .button("⬆ Top")
+                        .on_hover_text("Scroll to the top of the list")
+                        .clicked()
+                    {
+                        self.scroll_to_top = true;
+                    }
                 });
+        }
+
+        #[cfg(feature = "live")]
+        self.delete_confirm_dialog(ui.ctx());
+
+        if nr_pages > 1 && !grouped {
+            ui.horizontal(|ui| {
+                ui.style_mut().spacing.item_spacing.x = 5.0;
+                if ui
+                    .add_enabled(self.page > 0, egui::Button::new("⏴ Previous"))
+                    .clicked()
+                {
+                    self.page -= 1;
+                    self.update_visible_entries();
+                }
+                ui.label(format!("Page {} of {nr_pages}", self.page + 1));
+                if ui
+                    .add_enabled(self.page + 1 < nr_pages, egui::Button::new("Next ⏵"))
+                    .clicked()
+                {
+                    self.page += 1;
+                    self.update_visible_entries();
+                }
             });
+        }
 
         if self.filtered_entries.is_empty() {
             ui.horizontal(|ui| {
@@ -406,7 +4351,7 @@ impl ClickpackDb {
                     self.tags = Tags::default();
                     self.update_filtered_entries();
                 }
-                self.refresh_button(ui);
+                self.refresh_button(ui, req_fn);
             });
         } else if self.filtered_entries.len() <= 15 {
             ui.label(format!(
@@ -419,26 +4364,333 @@ impl ClickpackDb {
                 }
             ));
         }
+
+        self.suggestions_panel(ui);
+        self.footer(ui);
+    }
+
+    fn footer(&mut self, ui: &mut egui::Ui) {
+        let nr_downloaded = self.downloaded_count();
+        if nr_downloaded == 0 {
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.style_mut().spacing.item_spacing.x = 5.0;
+            ui.weak(format!(
+                "{} used across {nr_downloaded} pack{}",
+                format_size(self.total_downloaded_size(), DECIMAL),
+                if nr_downloaded == 1 { "" } else { "s" }
+            ));
+            #[cfg(feature = "live")]
+            if ui
+                .button("Delete all")
+                .on_hover_text("Delete all downloaded clickpacks from .zcb/clickpacks")
+                .clicked()
+            {
+                self.pending_delete_confirm = Some(PendingDelete::All);
+            }
+        });
+    }
+
+    /// Enqueues a downloaded clickpack's directory for deletion.
+    #[cfg(feature = "live")]
+    fn delete_entry(&mut self, name: &str) {
+        let Some(DownloadStatus::Downloaded { path, .. }) = self
+            .db
+            .read()
+            .unwrap()
+            .entries
+            .get(name)
+            .map(|entry| entry.dwn_status.clone())
+        else {
+            return;
+        };
+        log::info!("enqueuing clickpack {path:?} for deletion");
+        self.pending_clickpack_delete.push(path.clone());
+
+        // entries with the same URL were downloaded to the same directory
+        let mut names = vec![name.to_string()];
+        if let Some(url) = self.db.read().unwrap().entries.get(name).map(|e| &e.url) {
+            names.extend(self.shared_urls.get(url).into_iter().flatten().cloned());
+        }
+        for name in names {
+            if self.state.downloaded.get(&name) == Some(&path) {
+                self.state.downloaded.remove(&name);
+                self.set_download_status(&name, DownloadStatus::NotDownloaded);
+            }
+        }
+        self.save_state();
+    }
+
+    #[cfg(feature = "live")]
+    fn delete_all_entries(&mut self) {
+        let names: Vec<String> = self
+            .db
+            .read()
+            .unwrap()
+            .entries
+            .iter()
+            .filter(|(_, e)| matches!(e.dwn_status, DownloadStatus::Downloaded { .. }))
+            .map(|(k, _)| k.clone())
+            .collect();
+        for name in names {
+            self.delete_entry(&name);
+        }
+    }
+
+    #[cfg(feature = "live")]
+    fn delete_confirm_dialog(&mut self, ctx: &egui::Context) {
+        let Some(pending) = self.pending_delete_confirm.clone() else {
+            return;
+        };
+        let what = match pending {
+            PendingDelete::Entry(ref name) => self.display_name(name),
+            PendingDelete::All => "all downloaded clickpacks".to_string(),
+        };
+        let modal = egui::Modal::new(egui::Id::new("clickpackdb_delete_confirm")).show(ctx, |ui| {
+            ui.label(format!("Delete {what}? This cannot be undone."));
+            ui.horizontal(|ui| {
+                ui.style_mut().spacing.item_spacing.x = 5.0;
+                if ui.button("Yes").clicked() {
+                    Some(true)
+                } else if ui.button("No").clicked() {
+                    Some(false)
+                } else {
+                    None
+                }
+            })
+            .inner
+        });
+        match modal.inner {
+            Some(true) => {
+                match pending {
+                    PendingDelete::Entry(ref name) => self.delete_entry(name),
+                    PendingDelete::All => self.delete_all_entries(),
+                }
+                self.pending_delete_confirm = None;
+            }
+            Some(false) => self.pending_delete_confirm = None,
+            None if modal.should_close() => self.pending_delete_confirm = None,
+            None => {}
+        }
+    }
+
+    fn name_cell(&mut self, ui: &mut egui::Ui, name: &str, entry: &Entry, description_width: f32) {
+        let description = entry.description.as_deref().filter(|d| !d.is_empty());
+        if let Some(description) = description.filter(|_| self.expanded_entries.contains(name)) {
+            ui.vertical(|ui| {
+                self.name_line(ui, name, entry, true);
+                ui.add_sized(
+                    [description_width, 0.0],
+                    egui::Label::new(egui::RichText::new(description).weak()).wrap(),
+                );
+            });
+        } else {
+            self.name_line(ui, name, entry, description.is_some());
+        }
+    }
+
+    fn name_line(&mut self, ui: &mut egui::Ui, name: &str, entry: &Entry, has_description: bool) {
+        let mut text = egui::RichText::new(self.display_name(name));
+        if self.highlight_popular
+            && self
+                .popular_threshold
+                .is_some_and(|threshold| entry.downloads >= threshold)
+        {
+            text = text.strong().color(Color32::GOLD);
+        }
+        ui.horizontal(|ui| {
+            ui.style_mut().spacing.item_spacing.x = 5.0;
+            ui.add(egui::Label::new(text).wrap().sense(egui::Sense::click()))
+                .context_menu(|ui| self.entry_context_menu(ui, name, entry));
+            ui.style_mut().spacing.item_spacing.x = 5.0;
+            if self.new_entries.contains(name) {
+                let job = tag_text(ui, self.theme.new_badge_color, "NEW", "");
+                ui.label(job)
+                    .on_hover_text("Added since your last session")
+                    .on_hover_cursor(egui::CursorIcon::Default);
+            } else if self.whats_new.iter().any(|new| new == name) {
+                ui.colored_label(self.theme.new_badge_color, "🆕")
+                    .on_hover_text("New or updated since your last session")
+                    .on_hover_cursor(egui::CursorIcon::Default);
+            }
+            if entry.is_local {
+                ui.label("📁")
+                    .on_hover_text("Imported from a local file")
+                    .on_hover_cursor(egui::CursorIcon::Default);
+            }
+            if let Some(tags) = self.custom_tags.get(name) {
+                ui.colored_label(Color32::LIGHT_BLUE, "🏷")
+                    .on_hover_text(tags.join(", "))
+                    .on_hover_cursor(egui::CursorIcon::Default);
+            }
+            if entry.featured {
+                ui.colored_label(Color32::YELLOW, "⭐")
+                    .on_hover_text("Featured")
+                    .on_hover_cursor(egui::CursorIcon::Default);
+            }
+            if self.favorites.contains(name) {
+                ui.colored_label(Color32::GOLD, "★")
+                    .on_hover_text("Favorite")
+                    .on_hover_cursor(egui::CursorIcon::Default);
+            }
+            if entry.has_noise {
+                ui.colored_label(self.theme.noise_color, "🎧")
+                    .on_hover_text("This clickpack has a noise file")
+                    .on_hover_cursor(egui::CursorIcon::Default);
+            }
+            if entry.has_softclicks {
+                let job = tag_text(ui, Color32::LIGHT_BLUE, "🔉", "");
+                ui.label(job)
+                    .on_hover_text("This clickpack has softclicks")
+                    .on_hover_cursor(egui::CursorIcon::Default);
+            }
+            if entry.has_hardclicks {
+                let job = tag_text(ui, Color32::ORANGE, "🔊", "");
+                ui.label(job)
+                    .on_hover_text("This clickpack has hardclicks")
+                    .on_hover_cursor(egui::CursorIcon::Default);
+            }
+            if matches!(entry.dwn_status, DownloadStatus::Downloaded { .. }) {
+                ui.colored_label(self.theme.downloaded_color, "✅")
+                    .on_hover_text("Downloaded")
+                    .on_hover_cursor(egui::CursorIcon::Default);
+            }
+            if let Some(ref license) = entry.license {
+                ui.label("⚖")
+                    .on_hover_text(format!("License: {license}"))
+                    .on_hover_cursor(egui::CursorIcon::Default);
+            }
+            for system in &entry.compatible_with {
+                let chip = egui::Button::new(tag_text(
+                    ui,
+                    Color32::LIGHT_GREEN,
+                    "🎮",
+                    &format!(" {system}"),
+                ))
+                .small()
+                .rounding(8.0);
+                if ui
+                    .add(chip)
+                    .on_hover_text(format!("Compatible with {system}, click to filter"))
+                    .clicked()
+                {
+                    self.tags.compatible_with.insert(system.clone());
+                    self.update_filtered_entries();
+                }
+            }
+            if self.has_update(name, entry) {
+                ui.colored_label(self.theme.new_badge_color, "🔄")
+                    .on_hover_text("New version available")
+                    .on_hover_cursor(egui::CursorIcon::Default);
+            }
+            if has_description {
+                let expanded = self.expanded_entries.contains(name);
+                if ui
+                    .add(egui::Button::new("ℹ").small().frame(false))
+                    .on_hover_text(if expanded {
+                        "Hide description"
+                    } else {
+                        "Show description"
+                    })
+                    .clicked()
+                    && !self.expanded_entries.remove(name)
+                {
+                    self.expanded_entries.insert(name.to_string());
+                }
+            }
+        });
+    }
+
+    /// Height of an expanded description when wrapped to `width`.
+    fn description_height(ui: &egui::Ui, description: &str, width: f32) -> f32 {
+        let font_id = egui::TextStyle::Body.resolve(ui.style());
+        ui.fonts(|fonts| {
+            fonts
+                .layout(description.to_string(), font_id, Color32::WHITE, width)
+                .size()
+                .y
+        })
+    }
+
+    fn entry_context_menu(&mut self, ui: &mut egui::Ui, name: &str, entry: &Entry) {
+        let is_favorite = self.favorites.contains(name);
+        if ui
+            .button(if is_favorite {
+                "Remove from favorites"
+            } else {
+                "Add to favorites"
+            })
+            .clicked()
+        {
+            self.toggle_favorite(name);
+            ui.close_menu();
+        }
+        ui.menu_button("Add tag…", |ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.new_tag_input)
+                    .hint_text("Tag name")
+                    .desired_width(120.0),
+            );
+            response.request_focus();
+            if ui.button("Add").clicked()
+                || (response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)))
+            {
+                let tag = std::mem::take(&mut self.new_tag_input);
+                self.add_custom_tag(name, &tag);
+                ui.close_menu();
+            }
+        });
+        let entry_tags = self.custom_tags.get(name).cloned().unwrap_or_default();
+        for tag in entry_tags {
+            if ui.button(format!("Remove tag \"{tag}\"")).clicked() {
+                self.remove_custom_tag(name, &tag);
+                ui.close_menu();
+            }
+        }
+        ui.separator();
+        if ui
+            .button(if entry.has_noise {
+                "Mark as no noise"
+            } else {
+                "Mark as has noise"
+            })
+            .clicked()
+        {
+            self.set_has_noise_override(name, Some(!entry.has_noise));
+            ui.close_menu();
+        }
+        if self
+            .local_overrides
+            .get(name)
+            .is_some_and(|overrides| overrides.has_noise.is_some())
+            && ui.button("Reset noise to database value").clicked()
+        {
+            self.set_has_noise_override(name, None);
+            ui.close_menu();
+        }
+        ui.separator();
+        if ui.button("Copy URL").clicked() {
+            ui.ctx().copy_text(entry.url.clone());
+            ui.close_menu();
+        }
+        if ui.button("Copy name").clicked() {
+            ui.ctx().copy_text(name.to_string());
+            ui.close_menu();
+        }
     }
 
     fn manage_row(
         &mut self,
         ui: &mut egui::Ui,
-        entry: Entry,
-        name: String,
+        entry: &Entry,
+        name: &str,
         req_fn: &'static RequestFn,
         #[cfg(not(feature = "live"))] pick_folder: &'static PickFolderFn,
     ) {
         macro_rules! set_status {
             ($status:expr) => {
-                self.db
-                    .write()
-                    .unwrap()
-                    .entries
-                    .get_mut(&name)
-                    .unwrap()
-                    .dwn_status = $status;
-                self.update_filtered_entries();
+                self.set_download_status(&name, $status);
             };
         }
 
@@ -455,13 +4707,16 @@ impl ClickpackDb {
                             .clicked()
                         {
                             if let Some(path) = pick_folder() {
-                                set_status!(DownloadStatus::Downloading);
                                 self.download_entry(
                                     entry.clone(),
-                                    name.clone(),
+                                    name.to_string(),
                                     req_fn,
-                                    path,
-                                    false,
+                                    DownloadTarget {
+                                        path,
+                                        mode: DownloadMode::Extract { do_select: false },
+                                        owns_dir: false,
+                                    },
+                                    DEFAULT_MAX_RETRIES,
                                 );
                             }
                         }
@@ -479,51 +4734,171 @@ impl ClickpackDb {
                         })
                         .clicked()
                     {
-                        set_status!(DownloadStatus::Downloading);
-
-                        // create dir
-                        let mut new_name = name.clone();
+                        self.download_and_select(entry.clone(), name.to_string(), req_fn);
+                    }
+                    if ui
+                        .button("Download ZIP")
+                        .on_hover_text("Save the ZIP file of this clickpack without extracting it")
+                        .clicked()
+                    {
                         #[cfg(not(feature = "live"))]
-                        let mut path = {
-                            let mut path = std::env::temp_dir();
-                            path.push(TEMP_DIRNAME);
-                            path.push(&new_name);
-                            path
-                        };
+                        let path = pick_folder();
                         #[cfg(feature = "live")]
-                        let mut path = {
-                            let mut path = PathBuf::from(".zcb").join("clickpacks");
-                            path.push(&new_name);
-                            path
-                        };
-                        while path.try_exists().unwrap_or(false) {
-                            path.pop();
-                            new_name += "_";
-                            path.push(&new_name);
+                        let path = Some(self.download_dir());
+                        if let Some(path) = path {
+                            self.download_entry_zip(entry.clone(), name.to_string(), req_fn, path);
                         }
-
-                        let _ = std::fs::create_dir_all(&path)
-                            .map_err(|e| log::error!("create_dir_all failed: {e}"));
-
-                        // download clickpack zip & extract it
-                        self.download_entry(entry.clone(), name, req_fn, path, true);
+                    }
+                    if let Some(ref preview_url) = entry.preview_url {
+                        if ui
+                            .button("▶ Preview")
+                            .on_hover_text("Listen to a preview of this clickpack")
+                            .clicked()
+                        {
+                            self.fetch_preview_audio(name, preview_url, req_fn);
+                        }
+                    }
+                }
+                DownloadStatus::Queued { ref cancel, .. }
+                | DownloadStatus::Downloading { ref cancel, .. }
+                | DownloadStatus::Retrying { ref cancel, .. }
+                    if cancel.is_cancelled() =>
+                {
+                    ui.add(egui::Spinner::new());
+                    ui.label("Cancelling…");
+                }
+                DownloadStatus::Queued {
+                    position,
+                    ref cancel,
+                } => {
+                    ui.style_mut().spacing.item_spacing.x = 5.0;
+                    if ui
+                        .small_button("✕")
+                        .on_hover_text("Cancel this download")
+                        .clicked()
+                    {
+                        log::info!("cancelling download of \"{name}\" ({})", entry.dwn_status);
+                        cancel.cancel();
+                    }
+                    ui.label(format!("In queue: #{position}"))
+                        .on_hover_text("Waiting for other downloads to finish");
+                }
+                DownloadStatus::Downloading {
+                    started_at,
+                    received,
+                    total,
+                    ref cancel,
+                } => {
+                    ui.style_mut().spacing.item_spacing.x = 5.0;
+                    if ui
+                        .small_button("✕")
+                        .on_hover_text("Cancel this download")
+                        .clicked()
+                    {
+                        log::info!("cancelling download of \"{name}\" ({})", entry.dwn_status);
+                        cancel.cancel();
+                    }
+                    if let Some(total) = total.filter(|&total| total > 0) {
+                        ui.add(
+                            egui::ProgressBar::new(received as f32 / total as f32)
+                                .desired_width(100.0)
+                                .show_percentage(),
+                        )
+                        .on_hover_text(format!(
+                            "{} / {}",
+                            format_size(received, DECIMAL),
+                            format_size(total, DECIMAL)
+                        ));
+                        ui.ctx()
+                            .request_repaint_after(std::time::Duration::from_millis(100));
+                    } else {
+                        ui.add(egui::Spinner::new());
+                        ui.label("Downloading…");
+                    }
+                    if received > 0 {
+                        ui.weak(format_speed_eta(started_at, received, total));
                     }
                 }
-                DownloadStatus::Downloading => {
+                DownloadStatus::Retrying {
+                    attempt,
+                    max,
+                    ref error,
+                    ref cancel,
+                    mirror,
+                } => {
+                    ui.style_mut().spacing.item_spacing.x = 5.0;
+                    if ui
+                        .small_button("✕")
+                        .on_hover_text("Cancel this download")
+                        .clicked()
+                    {
+                        log::info!("cancelling download of \"{name}\" ({})", entry.dwn_status);
+                        cancel.cancel();
+                    }
                     ui.add(egui::Spinner::new());
-                    ui.label("Downloading…");
+                    ui.label(DownloadStatus::retrying_label(attempt, max, mirror))
+                        .on_hover_text(format!("Last error: {error}"));
                 }
                 DownloadStatus::Downloaded {
                     ref path,
-                    do_select,
+                    is_extracted: false,
+                    ..
                 } => {
                     ui.style_mut().spacing.item_spacing.x = 5.0;
                     #[cfg(not(feature = "live"))]
                     if ui.button("Open folder").clicked() {
-                        if let Err(e) = open::that(path) {
-                            log::error!("failed to open folder {path:?}: {e}");
+                        let _ = self.open_pack_folder(name);
+                    }
+                    #[cfg(feature = "live")]
+                    if ui
+                        .button("Delete")
+                        .on_hover_text("Delete this ZIP file")
+                        .clicked()
+                    {
+                        self.pending_delete_confirm = Some(PendingDelete::Entry(name.to_string()));
+                    }
+                    ui.weak("ZIP").on_hover_text(path.display().to_string());
+                }
+                DownloadStatus::Downloaded {
+                    ref path,
+                    do_select,
+                    ..
+                } => {
+                    ui.style_mut().spacing.item_spacing.x = 5.0;
+                    if self.has_update(name, entry)
+                        && ui
+                            .button("Re-download")
+                            .on_hover_text("Download the new version of this clickpack")
+                            .clicked()
+                    {
+                        self.download_entry(
+                            entry.clone(),
+                            name.to_string(),
+                            req_fn,
+                            DownloadTarget {
+                                path: path.clone(),
+                                mode: DownloadMode::Update,
+                                owns_dir: path.starts_with(self.download_dir()),
+                            },
+                            DEFAULT_MAX_RETRIES,
+                        );
+                    }
+                    if let Some(preview_callback) = &self.preview_callback {
+                        if ui
+                            .button("▶")
+                            .on_hover_text("Play a click from this clickpack")
+                            .clicked()
+                        {
+                            match find_preview_sound(path) {
+                                Some(sound) => preview_callback(sound),
+                                None => log::warn!("no click sounds found in {path:?}"),
+                            }
                         }
                     }
+                    #[cfg(not(feature = "live"))]
+                    if ui.button("Open folder").clicked() {
+                        let _ = self.open_pack_folder(name);
+                    }
                     if ui
                         .button("Select")
                         .on_hover_text("Select this clickpack as the current one")
@@ -534,10 +4909,10 @@ impl ClickpackDb {
                             set_status!(DownloadStatus::Downloaded {
                                 path: path.clone(),
                                 do_select: false,
+                                is_extracted: true,
                             });
                         }
-                        log::info!("selecting clickpack {path:?}");
-                        self.select_clickpack = Some(path.clone());
+                        self.select(name, path.clone());
                     }
                     ui.style_mut().spacing.item_spacing.x = 5.0;
                     #[cfg(feature = "live")]
@@ -546,13 +4921,34 @@ impl ClickpackDb {
                         .on_hover_text("Delete this clickpack from .zcb/clickpacks")
                         .clicked()
                     {
-                        log::info!("enqueuing clickpack {path:?} for deletion");
-                        self.pending_clickpack_delete.push(path.clone());
-                        set_status!(DownloadStatus::NotDownloaded);
+                        self.pending_delete_confirm = Some(PendingDelete::Entry(name.to_string()));
                     }
                 }
                 DownloadStatus::Error(ref e) => {
-                    ui.colored_label(egui::Color32::RED, format!("Error: {e}"));
+                    ui.style_mut().spacing.item_spacing.x = 5.0;
+                    if ui
+                        .button("Retry")
+                        .on_hover_text("Download this clickpack again")
+                        .clicked()
+                    {
+                        set_status!(DownloadStatus::NotDownloaded);
+                        let target = match self.download_targets.get(name) {
+                            Some(target) => target.clone(),
+                            None => DownloadTarget {
+                                path: self.unique_download_path(name),
+                                mode: DownloadMode::Extract { do_select: false },
+                                owns_dir: true,
+                            },
+                        };
+                        self.download_entry(
+                            entry.clone(),
+                            name.to_string(),
+                            req_fn,
+                            target,
+                            DEFAULT_MAX_RETRIES,
+                        );
+                    }
+                    ui.colored_label(self.theme.error_color, format!("Error: {e}"));
                 }
             }
 
@@ -564,3 +4960,139 @@ impl ClickpackDb {
         });
     }
 }
+
+/// Compact list of all in-progress and failed downloads of a [`ClickpackDb`],
+/// created with [`ClickpackDb::queue_panel`]. It is updated while the
+/// [`ClickpackDb`] is shown.
+pub struct ClickpackDbQueuePanel {
+    queue: Arc<RwLock<QueueState>>,
+    theme: ClickpackDbTheme,
+}
+
+impl ClickpackDbQueuePanel {
+    pub fn show(&self, ui: &mut egui::Ui) {
+        let queue = self.queue.read().unwrap();
+        if queue.items.is_empty() {
+            ui.weak("No downloads in progress");
+            return;
+        }
+        for (name, status) in &queue.items {
+            ui.horizontal(|ui| {
+                ui.style_mut().spacing.item_spacing.x = 5.0;
+                ui.label(name.replace('_', " "));
+                match *status {
+                    DownloadStatus::Queued {
+                        position,
+                        ref cancel,
+                    } => {
+                        ui.label(format!("In queue: #{position}"));
+                        if !cancel.is_cancelled()
+                            && ui
+                                .small_button("✕")
+                                .on_hover_text("Cancel this download")
+                                .clicked()
+                        {
+                            log::info!("cancelling download of \"{name}\"");
+                            cancel.cancel();
+                        }
+                    }
+                    DownloadStatus::Downloading {
+                        started_at,
+                        received,
+                        total,
+                        ref cancel,
+                    } => {
+                        if let Some(total) = total.filter(|&total| total > 0) {
+                            ui.add(
+                                egui::ProgressBar::new(received as f32 / total as f32)
+                                    .desired_width(100.0)
+                                    .show_percentage(),
+                            );
+                        } else {
+                            ui.add(egui::Spinner::new());
+                        }
+                        if received > 0 {
+                            ui.weak(format_speed_eta(started_at, received, total));
+                        }
+                        if !cancel.is_cancelled()
+                            && ui
+                                .small_button("✕")
+                                .on_hover_text("Cancel this download")
+                                .clicked()
+                        {
+                            log::info!("cancelling download of \"{name}\"");
+                            cancel.cancel();
+                        }
+                    }
+                    DownloadStatus::Retrying {
+                        attempt,
+                        max,
+                        ref error,
+                        ref cancel,
+                        mirror,
+                    } => {
+                        ui.add(egui::Spinner::new());
+                        ui.label(DownloadStatus::retrying_label(attempt, max, mirror))
+                            .on_hover_text(format!("Last error: {error}"));
+                        if !cancel.is_cancelled()
+                            && ui
+                                .small_button("✕")
+                                .on_hover_text("Cancel this download")
+                                .clicked()
+                        {
+                            log::info!("cancelling download of \"{name}\"");
+                            cancel.cancel();
+                        }
+                    }
+                    DownloadStatus::Error(ref e) => {
+                        ui.colored_label(self.theme.error_color, format!("Error: {e}"));
+                    }
+                    _ => {}
+                }
+            });
+        }
+        let is_downloading = queue.items.iter().any(|(_, status)| !status.is_terminal());
+        if is_downloading {
+            // the queue is only updated while the table is shown, but progress
+            // should still move here
+            ui.ctx()
+                .request_repaint_after(std::time::Duration::from_millis(100));
+        }
+    }
+}
+
+/// [`ClickpackDb`] as an [`egui::Widget`], for `ui.add(ClickpackDbWidget::new(&mut db, req_fn))`.
+/// Shows the same UI as [`ClickpackDb::show`].
+pub struct ClickpackDbWidget<'a> {
+    db: &'a mut ClickpackDb,
+    req_fn: &'static RequestFn,
+    #[cfg(not(feature = "live"))]
+    pick_folder: &'static PickFolderFn,
+}
+
+impl<'a> ClickpackDbWidget<'a> {
+    pub fn new(
+        db: &'a mut ClickpackDb,
+        req_fn: &'static RequestFn,
+        #[cfg(not(feature = "live"))] pick_folder: &'static PickFolderFn,
+    ) -> Self {
+        Self {
+            db,
+            req_fn,
+            #[cfg(not(feature = "live"))]
+            pick_folder,
+        }
+    }
+}
+
+impl egui::Widget for ClickpackDbWidget<'_> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        ui.scope(|ui| {
+            #[cfg(not(feature = "live"))]
+            self.db.show(ui, self.req_fn, self.pick_folder);
+            #[cfg(feature = "live")]
+            self.db.show(ui, self.req_fn);
+        })
+        .response
+    }
+}